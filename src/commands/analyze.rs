@@ -1,20 +1,32 @@
 use crate::{
     errors::CliError,
-    models::filter::Filter,
-    services::filter_service,
-    utils::{command, filesystem, paths},
+    models::{filter::Filter, filter_traits::FilterScoring},
+    services::{
+        api_client::ApiClient,
+        backup_service,
+        criteria_service::Criteria,
+        filter_service, pick_service,
+        report_service::{self, FilterRow, OutputFormat},
+        similarity_service,
+    },
+    utils::{command, config, filesystem, paths, settings},
 };
 
-use std::collections::HashSet;
+use clap::ValueEnum;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 fn import_filters(filters: &[Filter], live: bool) -> Result<(), CliError> {
     log::info!("Starting import of {} filters", filters.len());
 
+    let client = ApiClient::new(live);
     let mut success_count = 0;
     let mut failure_count = 0;
 
     for filter in filters {
-        match command::import_filter(filter.id, live) {
+        match command::import_filter(&client, filter.id, live) {
             Ok(_) => {
                 log::info!("Successfully imported filter {}", filter.id);
                 success_count += 1;
@@ -32,47 +44,122 @@ fn import_filters(filters: &[Filter], live: bool) -> Result<(), CliError> {
         failure_count
     );
 
+    if failure_count > 0 {
+        return Err(CliError::PartialFailure {
+            failed: failure_count,
+            total: filters.len(),
+        });
+    }
+
     Ok(())
 }
 
-pub fn run(
-    filename: String,
-    existing: &Option<String>,
-    count: usize,
-    open: bool,
-    live: bool,
-    offset: usize,
-    autoimport: bool,
-    verbose: bool,
-) -> Result<(), CliError> {
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Takes the highest scoring filters
+    Top,
+    /// Balances score against rule similarity to filters already picked
+    Mmr,
+}
+
+pub struct AnalyzeOptions {
+    pub filename: String,
+    pub existing: Option<String>,
+    pub count: usize,
+    pub open: bool,
+    pub live: bool,
+    pub offset: usize,
+    pub autoimport: bool,
+    pub verbose: bool,
+    pub scorer: String,
+    pub where_expr: Option<String>,
+    pub criteria: String,
+    pub max_per_outcome: Option<u32>,
+    pub select: SelectionMode,
+    pub lambda: f64,
+    pub format: Option<OutputFormat>,
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(options: AnalyzeOptions) -> Result<(), CliError> {
+    let AnalyzeOptions {
+        filename,
+        existing,
+        count,
+        open,
+        live,
+        offset,
+        autoimport,
+        verbose,
+        scorer,
+        where_expr,
+        criteria,
+        max_per_outcome,
+        select,
+        lambda,
+        format,
+        output,
+    } = options;
+
+    if !(0.0..=1.0).contains(&lambda) {
+        return Err(CliError::ConfigError(format!(
+            "--lambda must be between 0 and 1, got {}",
+            lambda
+        )));
+    }
+
+    let settings = settings::get();
+    let profile = settings.get_scoring_profile(&scorer)?;
+    let criteria = match where_expr {
+        Some(expr) => Criteria::parse(&expr)?,
+        None => Criteria::parse(settings.get_criteria(&criteria)?)?,
+    };
+    log::debug!("Selecting filters matching: {}", criteria.source());
+
     // Determine existing filters file path
-    let existing_path = existing
-        .clone()
-        .unwrap_or_else(|| paths::get_existing_path(live).to_string());
+    let existing_path = existing.unwrap_or_else(|| paths::get_existing_path(live).to_string());
 
     // Load and filter data
     let raw_data: Vec<Filter> = filesystem::load_data(filename)?;
     log::info!("Loaded {} filters from source file", raw_data.len());
 
     // Remove filters that already exist
-    let filtered_data = filter_service::remove_existing_filters(raw_data, &existing_path)?;
+    let existing_data: Vec<Filter> = filesystem::load_data(existing_path)?;
+    let filtered_data = filter_service::remove_existing_filters(raw_data, &existing_data);
     log::info!(
         "Found {} new filters after removing existing ones",
         filtered_data.len()
     );
 
     let unique_set: HashSet<Filter> = filtered_data.into_iter().collect();
-    let unique_data: Vec<Filter> = unique_set.into_iter().collect();
+    let mut unique_data: Vec<Filter> = unique_set.into_iter().collect();
     log::info!(
         "Found {} unique filters after deduplication",
         unique_data.len()
     );
 
+    // Risk metrics come from the pick database, which is only needed when the
+    // criteria read them or verbose output shows them
+    if criteria.uses_risk() {
+        let with_risk = pick_service::attach_risk(&mut unique_data, live)?;
+
+        if with_risk < unique_data.len() {
+            log::warn!(
+                "{} filters have no synced pick history and don't match criteria using risk fields, see `history sync --scanner`",
+                unique_data.len() - with_risk
+            );
+        }
+    } else if verbose {
+        if let Err(err) = pick_service::attach_risk(&mut unique_data, live) {
+            log::warn!("Could not load risk metrics: {}", err);
+        }
+    }
+
     // Filter by validity criteria
-    let valid_filters = filter_service::filter_valid_entries(unique_data);
+    let valid_filters = filter_service::filter_valid_entries(unique_data, &criteria, &profile)?;
     log::info!("Found {} valid filters", valid_filters.len());
 
-    let mut sorted_filters = filter_service::sort_by_score(valid_filters);
+    let mut sorted_filters = filter_service::sort_by_score(valid_filters, &profile);
 
     // Apply offset if specified
     if offset > 0 {
@@ -80,14 +167,64 @@ pub fn run(
         log::info!("Applied offset of {}", offset);
     }
 
-    // Limit to requested count
-    sorted_filters.truncate(count);
+    let mut quotas = settings.quotas.clone();
+    if max_per_outcome.is_some() {
+        quotas.max_per_outcome = max_per_outcome;
+    }
+
+    // Limit to requested count, keeping each market within its quota
+    let mut neighbours = HashMap::new();
+
+    if select == SelectionMode::Mmr {
+        sorted_filters = filter_service::select_mmr(
+            sorted_filters,
+            &existing_data,
+            &quotas,
+            count,
+            lambda,
+            &profile,
+        );
+
+        for filter in &sorted_filters {
+            let others = sorted_filters.iter().chain(&existing_data);
+            if let Some(neighbour) = similarity_service::nearest_neighbour(filter, others) {
+                neighbours.insert(filter.id, neighbour);
+            }
+        }
+    } else if quotas.is_empty() {
+        sorted_filters.truncate(count);
+    } else {
+        sorted_filters =
+            filter_service::select_with_quotas(sorted_filters, &existing_data, &quotas, count);
+    }
     log::info!("Selected top {} filters", sorted_filters.len());
 
+    if let Some(format) = format {
+        let base_url = config::get_web_base_url(live);
+        let rows: Vec<FilterRow> = sorted_filters
+            .iter()
+            .map(|filter| {
+                FilterRow::new(filter, filter.score_with(&profile).total(), base_url)
+                    .with_neighbour(neighbours.get(&filter.id))
+            })
+            .collect();
+        report_service::emit(&rows, format, output.as_deref())?;
+    }
+
     if autoimport {
+        if !sorted_filters.is_empty() {
+            backup_service::create(live, "analyze --autoimport")?;
+        }
         import_filters(&sorted_filters, live)?;
-    } else {
-        filter_service::display_filters(&sorted_filters, open, live, verbose)?;
+    } else if format.is_none() || open {
+        filter_service::display_filters(
+            &sorted_filters,
+            open,
+            live,
+            verbose,
+            &profile,
+            &neighbours,
+        )?;
     }
 
     Ok(())
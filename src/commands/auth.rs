@@ -0,0 +1,74 @@
+use std::io::{self, BufRead};
+
+use crate::{
+    errors::CliError,
+    services::api_client::ApiClient,
+    utils::credentials::{self, Credentials},
+};
+
+fn read_password(password_stdin: bool) -> Result<String, CliError> {
+    if password_stdin {
+        let mut password = String::new();
+        io::stdin().lock().read_line(&mut password)?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    Ok(rpassword::prompt_password("Password: ")?)
+}
+
+pub fn login(username: &str, password_stdin: bool) -> Result<(), CliError> {
+    let password = read_password(password_stdin)?;
+
+    if password.is_empty() {
+        return Err(CliError::AuthError("Password must not be empty".into()));
+    }
+
+    let response = ApiClient::account().login(username, &password)?;
+    let session = Credentials::new(username.to_string(), response.token);
+    credentials::save(&session)?;
+
+    log::info!(
+        "Logged in as {}. Credentials stored in {}",
+        username,
+        credentials::get_credentials_path()?.display()
+    );
+
+    Ok(())
+}
+
+pub fn logout() -> Result<(), CliError> {
+    if credentials::clear()? {
+        log::info!("Logged out, stored credentials removed");
+    } else {
+        log::info!("Not logged in");
+    }
+
+    Ok(())
+}
+
+pub fn whoami() -> Result<(), CliError> {
+    let stored = credentials::load()?.ok_or(CliError::NotLoggedIn)?;
+
+    if stored.expired {
+        return Err(CliError::SessionExpired);
+    }
+
+    let profile = ApiClient::account().current_user()?;
+    let username = if profile.username.is_empty() {
+        &stored.username
+    } else {
+        &profile.username
+    };
+
+    println!("Logged in as {}", username);
+
+    if let Some(id) = profile.id {
+        println!("Account ID: {}", id);
+    }
+
+    if let Some(email) = &profile.email {
+        println!("Email: {}", email);
+    }
+
+    Ok(())
+}
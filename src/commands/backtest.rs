@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        backtest_service,
+        report_service::{self, OutputFormat},
+    },
+    utils::{filesystem, paths},
+};
+
+pub struct BacktestOptions {
+    pub dataset: PathBuf,
+    pub ids: Vec<i32>,
+    pub filename: Option<String>,
+    pub live: bool,
+    pub format: OutputFormat,
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(options: BacktestOptions) -> Result<(), CliError> {
+    let matches = backtest_service::load_dataset(&options.dataset)?;
+    log::info!(
+        "Loaded {} matches from {}",
+        matches.len(),
+        options.dataset.display()
+    );
+
+    let filename = options
+        .filename
+        .unwrap_or_else(|| paths::get_existing_path(options.live));
+    let mut filters: Vec<Filter> = filesystem::load_data(filename)?;
+
+    if !options.ids.is_empty() {
+        filters.retain(|filter| options.ids.contains(&filter.id));
+    }
+
+    let mut rows = Vec::new();
+
+    for filter in &filters {
+        if let Some(reason) = backtest_service::unsupported(filter) {
+            log::warn!("Skipping filter {}: {}", filter.id, reason);
+            continue;
+        }
+
+        rows.push(backtest_service::backtest(filter, &matches));
+    }
+
+    log::info!("Backtested {} of {} filters", rows.len(), filters.len());
+
+    let skipped: usize = rows.iter().map(|row| row.skipped).sum();
+    if skipped > 0 {
+        log::warn!(
+            "{} match evaluations lacked a statistic some rule needs and were skipped",
+            skipped
+        );
+    }
+
+    report_service::emit(&rows, options.format, options.output.as_deref())
+}
@@ -1,28 +1,226 @@
 use crate::{
     errors,
     models::filter::Filter,
-    services::filter_service,
-    utils::{command, filesystem, paths},
+    services::{
+        api_client::ApiClient, backup_service, filter_service, pick_service, protection_service,
+    },
+    utils::{command, filesystem, paths, prompt, settings},
 };
 
-pub fn run(live: bool) -> Result<(), errors::CliError> {
+pub struct CleanupOptions {
+    pub live: bool,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub roi_below: Option<f32>,
+    pub picks_above: Option<u32>,
+    pub decay: bool,
+    pub decay_since: Option<String>,
+    pub decay_roi_below: Option<f32>,
+    pub max_drawdown: Option<f64>,
+    pub max_losing_streak: Option<u32>,
+    pub max_volatility: Option<f64>,
+    pub max_recovery: Option<u32>,
+}
+
+/// A filter selected for deletion and why.
+struct Candidate {
+    filter: Filter,
+    reason: String,
+}
+
+fn print_table(rows: &[(&Candidate, &str)]) {
+    println!(
+        "{:>10}  {:<24}  {:<12}  {:>8}  {:>6}  {:<32}  Result",
+        "ID", "Name", "Outcome", "ROI", "Picks", "Reason"
+    );
+
+    for (Candidate { filter, reason }, result) in rows {
+        println!(
+            "{:>10}  {:<24}  {:<12}  {:>7.2}%  {:>6}  {:<32}  {}",
+            filter.id,
+            filter.name.chars().take(24).collect::<String>(),
+            filter
+                .desired_outcome
+                .as_ref()
+                .map(|m| m.to_string())
+                .unwrap_or_default(),
+            filter.roi,
+            filter.total_picks,
+            reason,
+            result
+        );
+    }
+}
+
+pub fn run(options: CleanupOptions) -> Result<(), errors::CliError> {
+    let live = options.live;
+
+    let mut thresholds = settings::get().cleanup.clone().unwrap_or_default();
+    if let Some(roi_below) = options.roi_below {
+        thresholds.roi_below = roi_below;
+    }
+    if let Some(picks_above) = options.picks_above {
+        thresholds.picks_above = picks_above;
+    }
+    if let Some(since) = options.decay_since {
+        thresholds.decay.since = since;
+    }
+    if let Some(roi_below) = options.decay_roi_below {
+        thresholds.decay.roi_below = roi_below;
+    }
+    if options.max_drawdown.is_some() {
+        thresholds.risk.max_drawdown = options.max_drawdown;
+    }
+    if options.max_losing_streak.is_some() {
+        thresholds.risk.max_losing_streak = options.max_losing_streak;
+    }
+    if options.max_volatility.is_some() {
+        thresholds.risk.max_volatility = options.max_volatility;
+    }
+    if options.max_recovery.is_some() {
+        thresholds.risk.max_recovery = options.max_recovery;
+    }
+
     // Get path to the existing file
     let filename = paths::get_existing_path(live);
 
     // Load data from file
-    let data: Vec<Filter> = filesystem::load_data(filename.to_string())?;
+    let mut data: Vec<Filter> = filesystem::load_data(filename.to_string())?;
+
+    // Filters that decayed recently, checked before lifetime ROI so that a
+    // filter matching both is reported with its recent performance
+    let mut candidates = Vec::new();
+
+    if options.decay {
+        let decaying = filter_service::find_decaying(&data, live, &thresholds.decay)?;
+        log::info!(
+            "Found {} filters with ROI below {:.2}% over at least {} picks since {}",
+            decaying.len(),
+            thresholds.decay.roi_below,
+            thresholds.decay.min_picks,
+            thresholds.decay.since
+        );
+
+        candidates.extend(decaying.into_iter().map(|(filter, window)| Candidate {
+            filter,
+            reason: format!("recent ROI {:.2}% over {} picks", window.roi, window.picks),
+        }));
+    }
+
+    // Filters whose pick history is too painful to follow
+    if !thresholds.risk.is_empty() {
+        let with_risk = pick_service::attach_risk(&mut data, live)?;
+        if with_risk < data.len() {
+            log::warn!(
+                "{} filters have no synced pick history and are not checked for risk, see `history sync`",
+                data.len() - with_risk
+            );
+        }
+
+        let mut risky = 0;
+
+        for filter in &data {
+            let Some(reason) = filter
+                .risk
+                .as_ref()
+                .and_then(|risk| thresholds.risk.exceeded_by(risk))
+            else {
+                continue;
+            };
+
+            if !candidates.iter().any(|c| c.filter.id == filter.id) {
+                risky += 1;
+                candidates.push(Candidate {
+                    filter: filter.clone(),
+                    reason,
+                });
+            }
+        }
+
+        log::info!("Found {} filters exceeding risk limits", risky);
+    }
 
     // Get only low performing filters
-    let filters_to_delete = filter_service::filter_low_performing(data);
+    let low_performing = filter_service::filter_low_performing(data, &thresholds);
+    log::info!(
+        "Found {} filters with ROI below {:.2}% and more than {} picks",
+        low_performing.len(),
+        thresholds.roi_below,
+        thresholds.picks_above
+    );
+
+    for filter in low_performing {
+        if !candidates.iter().any(|c| c.filter.id == filter.id) {
+            candidates.push(Candidate {
+                filter,
+                reason: "lifetime ROI".to_string(),
+            });
+        }
+    }
+
+    // Never touch protected filters
+    let protected = protection_service::load()?;
+    let (skipped, filters_to_delete): (Vec<Candidate>, Vec<Candidate>) = candidates
+        .into_iter()
+        .partition(|candidate| protected.is_protected(&candidate.filter));
+
+    for candidate in &skipped {
+        log::info!("Skipping protected filter {}", candidate.filter.id);
+    }
 
     log::info!("Found {} filters to delete", filters_to_delete.len());
 
-    for filter in filters_to_delete {
-        match command::delete_filter(filter.id, live) {
-            Ok(_) => log::info!("Successfully deleted filter {}", filter.id),
-            Err(err) => log::error!("Failed to delete filter {}: {}", filter.id, err),
+    if filters_to_delete.is_empty() {
+        return Ok(());
+    }
+
+    if options.dry_run {
+        let rows: Vec<_> = filters_to_delete
+            .iter()
+            .map(|candidate| (candidate, "would delete"))
+            .collect();
+        print_table(&rows);
+        return Ok(());
+    }
+
+    if !options.yes && !prompt::confirm(&format!("Delete {} filters?", filters_to_delete.len()))? {
+        log::info!("Aborted, no filters were deleted");
+        return Ok(());
+    }
+
+    backup_service::create(live, "cleanup")?;
+
+    let client = ApiClient::new(live);
+
+    let mut rows = Vec::new();
+
+    for candidate in &filters_to_delete {
+        let filter = &candidate.filter;
+
+        match command::delete_filter(&client, filter, live) {
+            Ok(_) => {
+                log::info!("Successfully deleted filter {}", filter.id);
+                rows.push((candidate, "deleted"));
+            }
+            Err(err) => {
+                log::error!("Failed to delete filter {}: {}", filter.id, err);
+                rows.push((candidate, "failed"));
+            }
         }
     }
 
+    print_table(&rows);
+
+    let failed = rows
+        .iter()
+        .filter(|(_, result)| *result == "failed")
+        .count();
+    if failed > 0 {
+        return Err(errors::CliError::PartialFailure {
+            failed,
+            total: rows.len(),
+        });
+    }
+
     Ok(())
 }
@@ -0,0 +1,21 @@
+use crate::{errors::CliError, utils::settings};
+
+/// Prints the effective settings as TOML, so the output can be used as a
+/// config file itself.
+pub fn show() -> Result<(), CliError> {
+    let settings = settings::get();
+
+    if settings.sources.is_empty() {
+        log::info!("No config files found, using built-in defaults");
+    }
+    for source in &settings.sources {
+        log::info!("Loaded {}", source.display());
+    }
+
+    let effective = toml::to_string_pretty(settings)
+        .map_err(|e| CliError::ConfigError(format!("Failed to render config: {}", e)))?;
+
+    print!("{}", effective);
+
+    Ok(())
+}
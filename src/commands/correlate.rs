@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        correlation_service, pick_service,
+        report_service::{self, OutputFormat},
+    },
+    utils::{filesystem, paths},
+};
+
+pub struct CorrelateOptions {
+    pub ids: Vec<i32>,
+    pub live: bool,
+    pub min_shared: usize,
+    pub format: OutputFormat,
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(options: CorrelateOptions) -> Result<(), CliError> {
+    let mut filters: Vec<Filter> = filesystem::load_data(paths::get_existing_path(options.live))?;
+
+    if !options.ids.is_empty() {
+        filters.retain(|filter| options.ids.contains(&filter.id));
+    }
+
+    let picks = pick_service::load_all(options.live)?;
+    let synced = filters
+        .iter()
+        .filter(|filter| picks.contains_key(&filter.id))
+        .count();
+
+    if synced < 2 {
+        log::info!(
+            "Pick history is synced for {} of {} filters, run `history sync` first",
+            synced,
+            filters.len()
+        );
+        return Ok(());
+    }
+
+    if synced < filters.len() {
+        log::warn!(
+            "{} filters have no synced picks and are left out",
+            filters.len() - synced
+        );
+    }
+
+    let rows = correlation_service::correlate(&filters, &picks, options.min_shared);
+    log::info!(
+        "Found {} pairs of filters sharing at least {} matches",
+        rows.len(),
+        options.min_shared.max(1)
+    );
+
+    report_service::emit(&rows, options.format, options.output.as_deref())
+}
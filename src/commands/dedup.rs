@@ -1,45 +1,369 @@
+use clap::ValueEnum;
+use std::{collections::HashSet, path::PathBuf};
+
 use crate::errors;
 use crate::models::filter::Filter;
-use crate::services::filter_service;
-use crate::utils::{config, filesystem, paths};
+use crate::models::filter_traits::FilterScoring;
+use crate::services::report_service::{self, FilterRow, OutputFormat};
+use crate::services::{api_client::ApiClient, backup_service, filter_service, similarity_service};
+use crate::utils::{command, config, filesystem, paths, prompt};
 
-pub fn run(filename: String, live: bool) -> Result<(), errors::CliError> {
-    let file_path = if filename.is_empty() {
-        paths::get_existing_path(live).to_string()
-    } else {
-        filename.clone()
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ResolveStrategy {
+    /// Keeps the filter with the highest score
+    #[value(name = "keep-best")]
+    Best,
+    /// Keeps the filter with the lowest ID
+    #[value(name = "keep-oldest")]
+    Oldest,
+    /// Keeps the filter with the most picks
+    #[value(name = "keep-most-picks")]
+    MostPicks,
+}
+
+pub struct ResolveOptions {
+    pub strategy: ResolveStrategy,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+fn choose_survivor<'a>(group: &[&'a Filter], strategy: ResolveStrategy) -> &'a Filter {
+    let survivor = match strategy {
+        ResolveStrategy::Best => group.iter().max_by(|a, b| {
+            a.get_score()
+                .partial_cmp(&b.get_score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        ResolveStrategy::Oldest => group.iter().min_by_key(|filter| filter.id),
+        ResolveStrategy::MostPicks => group.iter().max_by_key(|filter| filter.total_picks),
     };
 
-    let data: Vec<Filter> = filesystem::load_data(file_path)?;
-    log::info!("Loaded {} filters to duplicate analysis", data.len());
+    survivor.expect("duplicate groups are never empty")
+}
+
+fn join_ids(ids: &[i32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn resolve_duplicates(
+    data: &[Filter],
+    live: bool,
+    options: &ResolveOptions,
+) -> Result<(), errors::CliError> {
+    let duplicates = filter_service::find_duplicates(data);
+
+    let mut to_delete: Vec<&Filter> = Vec::new();
+
+    for ids in duplicates.values() {
+        let group: Vec<&Filter> = data.iter().filter(|f| ids.contains(&f.id)).collect();
+        let survivor = choose_survivor(&group, options.strategy);
+
+        println!(
+            "Keeping filter {} (ROI {:.2}%, Picks {}, Score {:.2})",
+            survivor.id,
+            survivor.roi,
+            survivor.total_picks,
+            survivor.get_score()
+        );
+
+        for filter in group.into_iter().filter(|f| f.id != survivor.id) {
+            println!(
+                "  {} filter {} (ROI {:.2}%, Picks {}, Score {:.2})",
+                if options.dry_run {
+                    "Would delete"
+                } else {
+                    "Will delete"
+                },
+                filter.id,
+                filter.roi,
+                filter.total_picks,
+                filter.get_score()
+            );
+            to_delete.push(filter);
+        }
+    }
+
+    if to_delete.is_empty() {
+        log::info!("Nothing to resolve");
+        return Ok(());
+    }
+
+    if options.dry_run {
+        log::info!(
+            "Dry run completed. {} filters would be deleted",
+            to_delete.len()
+        );
+        return Ok(());
+    }
+
+    if !options.yes && !prompt::confirm(&format!("Delete {} duplicate filters?", to_delete.len()))?
+    {
+        log::info!("Aborted, no filters were deleted");
+        return Ok(());
+    }
+
+    backup_service::create(live, "dedup --resolve")?;
+
+    let client = ApiClient::new(live);
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+
+    for filter in to_delete {
+        match command::delete_filter(&client, filter, live) {
+            Ok(_) => {
+                log::info!("Successfully deleted filter {}", filter.id);
+                deleted.push(filter.id);
+            }
+            Err(err) => {
+                log::error!("Failed to delete filter {}: {}", filter.id, err);
+                failed.push(filter.id);
+            }
+        }
+    }
+
+    log::info!(
+        "Resolve completed. Deleted: {}, Failures: {}",
+        deleted.len(),
+        failed.len()
+    );
+
+    if !deleted.is_empty() {
+        println!("Deleted filter IDs: {}", join_ids(&deleted));
+    }
 
-    let duplicates = filter_service::find_duplicates(&data);
+    if !failed.is_empty() {
+        println!("Failed filter IDs: {}", join_ids(&failed));
+        return Err(errors::CliError::PartialFailure {
+            failed: failed.len(),
+            total: deleted.len() + failed.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn report_duplicates(data: &[Filter], base_url: &str) {
+    let duplicates = filter_service::find_duplicates(data);
     log::info!("Found {} sets of duplicate filters", duplicates.len());
 
     // If no duplicates found, log and return
     if duplicates.is_empty() {
         log::info!("No duplicate filters found");
-        return Ok(());
+        return;
     }
 
-    let base_url = config::get_web_base_url(live);
-
     for (filter, ids) in duplicates {
-        log::info!("\nIdentical filters found ({} duplicates):", ids.len());
+        println!("\nIdentical filters found ({} duplicates):", ids.len());
         if let Some(outcome) = &filter.desired_outcome {
-            log::info!("Desired outcome: {}", outcome);
+            println!("Desired outcome: {} ({})", outcome, outcome.category());
         }
 
-        log::info!(
+        println!(
             "ROI: {:.2}%, Success Rate: {:.2}%",
-            filter.roi,
-            filter.success_rate
+            filter.roi, filter.success_rate
         );
 
         for id in ids {
             let url = format!("{}/{}/history", base_url, id);
-            log::info!("Filter ID {}: {}", id, url);
+            println!("Filter ID {}: {}", id, url);
+        }
+    }
+}
+
+fn report_subsumptions(data: &[Filter], base_url: &str) {
+    let pairs = filter_service::find_subsumptions(data);
+    let dominated: HashSet<i32> = pairs.iter().map(|(_, narrower)| narrower.id).collect();
+    log::info!(
+        "Found {} dominated filters in {} pairs",
+        dominated.len(),
+        pairs.len()
+    );
+
+    if pairs.is_empty() {
+        return;
+    }
+
+    for (broader, narrower) in pairs {
+        println!(
+            "\nFilter {} picks a subset of filter {}'s matches:",
+            narrower.id, broader.id
+        );
+        if let Some(outcome) = &broader.desired_outcome {
+            println!("Desired outcome: {} ({})", outcome, outcome.category());
+        }
+
+        println!("{:<14}{:>12}{:>12}", "", "Broader", "Narrower");
+        println!("{:<14}{:>12}{:>12}", "ID", broader.id, narrower.id);
+        println!("{:<14}{:>11.2}%{:>11.2}%", "ROI", broader.roi, narrower.roi);
+        println!(
+            "{:<14}{:>11.2}%{:>11.2}%",
+            "Success Rate", broader.success_rate, narrower.success_rate
+        );
+        println!(
+            "{:<14}{:>12}{:>12}",
+            "Picks", broader.total_picks, narrower.total_picks
+        );
+        println!(
+            "{:<14}{:>12}{:>12}",
+            "Rules",
+            broader.rules.len(),
+            narrower.rules.len()
+        );
+
+        for filter in [broader, narrower] {
+            println!(
+                "Filter ID {}: {}/{}/history",
+                filter.id, base_url, filter.id
+            );
+        }
+    }
+}
+
+fn report_clusters(data: &[Filter], base_url: &str, threshold: f64) {
+    let clusters = similarity_service::find_clusters(data, threshold);
+    log::info!(
+        "Found {} clusters of near-identical filters (threshold {:.2})",
+        clusters.len(),
+        threshold
+    );
+
+    for mut cluster in clusters {
+        cluster.sort_by(|a, b| {
+            b.get_score()
+                .partial_cmp(&a.get_score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let lead = cluster[0];
+        println!("\nNear-identical filters ({} filters):", cluster.len());
+        if let Some(outcome) = &lead.desired_outcome {
+            println!("Desired outcome: {} ({})", outcome, outcome.category());
+        }
+
+        for filter in cluster {
+            println!(
+                "Filter ID {}: similarity {:.2}, ROI {:.2}%, Success Rate {:.2}%, Picks {}, Score {:.2}, {}/{}/history",
+                filter.id,
+                similarity_service::filter_similarity(lead, filter),
+                filter.roi,
+                filter.success_rate,
+                filter.total_picks,
+                filter.get_score(),
+                base_url,
+                filter.id
+            );
+        }
+    }
+}
+
+/// Collects every reported filter as a row, numbering duplicate sets,
+/// dominated pairs and clusters as groups in the order they are reported.
+fn collect_rows(
+    data: &[Filter],
+    base_url: &str,
+    subsumption: bool,
+    fuzzy: Option<f64>,
+) -> Vec<FilterRow> {
+    let row = |filter: &Filter| FilterRow::new(filter, filter.get_score(), base_url);
+    let mut rows = Vec::new();
+    let mut group = 0;
+
+    let mut duplicates: Vec<Vec<i32>> = filter_service::find_duplicates(data)
+        .into_values()
+        .map(|mut ids| {
+            ids.sort();
+            ids
+        })
+        .collect();
+    duplicates.sort();
+
+    for ids in duplicates {
+        group += 1;
+        for filter in data.iter().filter(|filter| ids.contains(&filter.id)) {
+            rows.push(row(filter).in_group(group, "duplicate"));
+        }
+    }
+
+    if subsumption {
+        for (broader, narrower) in filter_service::find_subsumptions(data) {
+            group += 1;
+            rows.push(row(broader).in_group(group, "broader"));
+            rows.push(row(narrower).in_group(group, "narrower"));
+        }
+    }
+
+    if let Some(threshold) = fuzzy {
+        for cluster in similarity_service::find_clusters(data, threshold) {
+            group += 1;
+            for filter in cluster {
+                rows.push(row(filter).in_group(group, "similar"));
+            }
+        }
+    }
+
+    rows
+}
+
+pub struct DedupOptions {
+    pub filename: String,
+    pub live: bool,
+    pub subsumption: bool,
+    pub fuzzy: Option<f64>,
+    pub resolve: Option<ResolveOptions>,
+    pub format: Option<OutputFormat>,
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(options: DedupOptions) -> Result<(), errors::CliError> {
+    let DedupOptions {
+        filename,
+        live,
+        subsumption,
+        fuzzy,
+        resolve,
+        format,
+        output,
+    } = options;
+
+    if let Some(threshold) = fuzzy.filter(|t| !(0.0..=1.0).contains(t)) {
+        return Err(errors::CliError::ConfigError(format!(
+            "--threshold must be between 0 and 1, got {}",
+            threshold
+        )));
+    }
+
+    let file_path = if filename.is_empty() {
+        paths::get_existing_path(live).to_string()
+    } else {
+        filename.clone()
+    };
+
+    let data: Vec<Filter> = filesystem::load_data(file_path)?;
+    log::info!("Loaded {} filters to duplicate analysis", data.len());
+
+    let base_url = config::get_web_base_url(live);
+
+    if let Some(format) = format {
+        let rows = collect_rows(&data, base_url, subsumption, fuzzy);
+        report_service::emit(&rows, format, output.as_deref())?;
+    } else {
+        report_duplicates(&data, base_url);
+
+        if subsumption {
+            report_subsumptions(&data, base_url);
         }
+
+        if let Some(threshold) = fuzzy {
+            report_clusters(&data, base_url, threshold);
+        }
+    }
+
+    if let Some(options) = resolve {
+        resolve_duplicates(&data, live, &options)?;
     }
 
     Ok(())
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        diff_service::{self, Change},
+        report_service::{self, OutputFormat},
+        snapshot_service,
+    },
+    utils::{filesystem, paths, time},
+};
+
+pub struct DiffOptions {
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub since: Option<String>,
+    pub live: bool,
+    pub format: OutputFormat,
+    pub output: Option<PathBuf>,
+}
+
+/// Loads a snapshot given either as a file path or as an archived snapshot ID.
+fn load_snapshot(reference: &str) -> Result<Vec<Filter>, CliError> {
+    if Path::new(reference).exists() {
+        return filesystem::load_data(reference.to_string());
+    }
+
+    let archived = snapshot_service::find_archived(reference)?;
+    filesystem::load_data(archived.path.to_string_lossy().to_string())
+}
+
+/// Picks the archived fetch to compare the current snapshot against: the
+/// newest one from before `since`, or else the one before the current fetch.
+fn find_baseline(since: Option<&str>, live: bool) -> Result<String, CliError> {
+    let archived = snapshot_service::list_archived(live)?;
+
+    let cutoff = match since {
+        Some(since) => time::parse_point_in_time(since)?,
+        None => snapshot_service::load_meta(&paths::get_existing_path(live), live)
+            .map(|meta| meta.fetched_at)
+            .ok_or_else(|| {
+                CliError::SnapshotUnavailable("No current snapshot to compare".into())
+            })?,
+    };
+
+    archived
+        .iter()
+        .rev()
+        .find(|snapshot| snapshot.fetched_at.timestamp() < cutoff.timestamp())
+        .map(|snapshot| snapshot.id.clone())
+        .ok_or_else(|| {
+            CliError::SnapshotUnavailable(match archived.first() {
+                Some(oldest) => format!(
+                    "No archived snapshot before {}, the oldest is {}",
+                    cutoff.format("%Y-%m-%d %H:%M:%S"),
+                    oldest.id
+                ),
+                None => "No archived snapshots yet, they are kept on every fetch".into(),
+            })
+        })
+}
+
+pub fn list(live: bool) -> Result<(), CliError> {
+    let archived = snapshot_service::list_archived(live)?;
+
+    if archived.is_empty() {
+        log::info!("No archived snapshots found");
+        return Ok(());
+    }
+
+    for snapshot in archived {
+        println!("{}", snapshot.id);
+    }
+
+    Ok(())
+}
+
+pub fn run(options: DiffOptions) -> Result<(), CliError> {
+    let DiffOptions {
+        before,
+        after,
+        since,
+        live,
+        format,
+        output,
+    } = options;
+
+    let before = match before {
+        Some(before) => before,
+        None => find_baseline(since.as_deref(), live)?,
+    };
+    let after = after.unwrap_or_else(|| paths::get_existing_path(live));
+
+    log::info!("Comparing {} with {}", before, after);
+
+    let rows = diff_service::diff(&load_snapshot(&before)?, &load_snapshot(&after)?);
+
+    let count = |change: Change| rows.iter().filter(|row| row.change == change).count();
+    log::info!(
+        "Added: {}, Removed: {}, Renamed: {}, Changed: {}",
+        count(Change::Added),
+        count(Change::Removed),
+        count(Change::Renamed),
+        count(Change::Changed)
+    );
+
+    report_service::emit(&rows, format, output.as_deref())
+}
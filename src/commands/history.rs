@@ -0,0 +1,185 @@
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        api_client::ApiClient,
+        backup_service,
+        journal_service::{self, Action, JournalEntry},
+        snapshot_service::{self, SnapshotKind},
+    },
+    utils::{command, prompt, time},
+};
+
+pub struct HistoryQuery {
+    pub since: Option<String>,
+    pub action: Option<Action>,
+    pub id: Option<i32>,
+}
+
+fn describe(entry: &JournalEntry) -> String {
+    match entry.action {
+        Action::Import => format!(
+            "{} -> {} '{}'",
+            entry.filter_id,
+            entry.new_id.map_or("?".to_string(), |id| id.to_string()),
+            entry.after_name.as_deref().unwrap_or_default()
+        ),
+        Action::Delete => format!(
+            "{} '{}'",
+            entry.filter_id,
+            entry.before_name.as_deref().unwrap_or_default()
+        ),
+        Action::Rename => format!(
+            "{} '{}' -> '{}'",
+            entry.filter_id,
+            entry.before_name.as_deref().unwrap_or_default(),
+            entry.after_name.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+pub fn list(query: HistoryQuery) -> Result<(), CliError> {
+    let since = query
+        .since
+        .as_deref()
+        .map(time::parse_point_in_time)
+        .transpose()?;
+
+    let entries: Vec<JournalEntry> = journal_service::load()?
+        .into_iter()
+        .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+        .filter(|entry| query.action.is_none_or(|action| entry.action == action))
+        .filter(|entry| {
+            query
+                .id
+                .is_none_or(|id| entry.filter_id == id || entry.new_id == Some(id))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        log::info!("No journal entries found");
+        return Ok(());
+    }
+
+    for entry in entries {
+        println!(
+            "{:>5}  {}  {:<6}  {:<4}  {:<6}  {}  ({})",
+            entry.id,
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.action,
+            if entry.live { "live" } else { "pre" },
+            if entry.succeeded() { "ok" } else { "failed" },
+            describe(&entry),
+            entry.command
+        );
+    }
+
+    Ok(())
+}
+
+fn find_current(current: &[Filter], id: i32) -> Result<&Filter, CliError> {
+    current
+        .iter()
+        .find(|filter| filter.id == id)
+        .ok_or_else(|| CliError::JournalError(format!("Filter {} no longer exists", id)))
+}
+
+pub fn undo(entry_id: u64, dry_run: bool, yes: bool) -> Result<(), CliError> {
+    let entry = journal_service::load()?
+        .into_iter()
+        .find(|entry| entry.id == entry_id)
+        .ok_or_else(|| CliError::JournalError(format!("No journal entry {}", entry_id)))?;
+
+    if !entry.succeeded() {
+        return Err(CliError::JournalError(format!(
+            "Entry {} failed and has nothing to undo",
+            entry_id
+        )));
+    }
+
+    let live = entry.live;
+
+    // Compare against what is on the server right now, not a cached snapshot.
+    // It is only stored once the undo goes ahead, so a dry run changes nothing.
+    let client = ApiClient::new(live);
+    let current = snapshot_service::fetch_filters(&client, SnapshotKind::Existing)?;
+
+    let question = match entry.action {
+        Action::Import => {
+            let new_id = entry.new_id.ok_or_else(|| {
+                CliError::JournalError(format!("Entry {} has no imported filter ID", entry_id))
+            })?;
+            let filter = find_current(&current, new_id)?;
+            format!("Delete imported filter {} '{}'?", filter.id, filter.name)
+        }
+        Action::Delete => {
+            let source_id = entry.source_id.ok_or_else(|| {
+                CliError::JournalError(format!(
+                    "Filter {} has no source ID to re-import from",
+                    entry.filter_id
+                ))
+            })?;
+            if current.iter().any(|f| f.source_id == Some(source_id)) {
+                return Err(CliError::JournalError(format!(
+                    "Source {} is already imported again",
+                    source_id
+                )));
+            }
+            format!(
+                "Re-import deleted filter {} from source {}?",
+                entry.filter_id, source_id
+            )
+        }
+        Action::Rename => {
+            let filter = find_current(&current, entry.filter_id)?;
+            if Some(&filter.name) != entry.after_name.as_ref() {
+                return Err(CliError::JournalError(format!(
+                    "Filter {} was renamed again since, now '{}'",
+                    filter.id, filter.name
+                )));
+            }
+            format!(
+                "Rename filter {} back to '{}'?",
+                filter.id,
+                entry.before_name.as_deref().unwrap_or_default()
+            )
+        }
+    };
+
+    if dry_run {
+        println!(
+            "Dry run completed. Would undo entry {}: {}",
+            entry_id, question
+        );
+        return Ok(());
+    }
+
+    if !yes && !prompt::confirm(&question)? {
+        log::info!("Aborted, nothing was undone");
+        return Ok(());
+    }
+
+    snapshot_service::store_snapshot(&current, SnapshotKind::Existing, live)?;
+    backup_service::create(live, &format!("undo {}", entry_id))?;
+
+    match entry.action {
+        Action::Import => {
+            let filter = find_current(&current, entry.new_id.unwrap_or_default())?;
+            command::delete_filter(&client, filter, live)?;
+            log::info!("Deleted imported filter {}", filter.id);
+        }
+        Action::Delete => {
+            let imported =
+                command::import_filter(&client, entry.source_id.unwrap_or_default(), live)?;
+            log::info!("Re-imported filter {} as {}", entry.filter_id, imported.id);
+        }
+        Action::Rename => {
+            let filter = find_current(&current, entry.filter_id)?;
+            let name = entry.before_name.as_deref().unwrap_or_default();
+            command::rename_filter(&client, filter, name, live)?;
+            log::info!("Renamed filter {} back to '{}'", filter.id, name);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use chrono::Utc;
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{api_client::ApiClient, pick_service},
+    utils::{filesystem, paths},
+};
+
+/// Downloads the pick history of the account's filters, or of the scanner's
+/// with `scanner`, into the local store. Only `ids` are synced when given.
+pub fn sync(client: &ApiClient, ids: &[i32], live: bool, scanner: bool) -> Result<(), CliError> {
+    let path = if scanner {
+        paths::get_data_path(live)
+    } else {
+        paths::get_existing_path(live)
+    };
+    let mut filters: Vec<Filter> = filesystem::load_data(path)?;
+
+    if !ids.is_empty() {
+        filters.retain(|filter| ids.contains(&filter.id));
+
+        for id in ids {
+            if !filters.iter().any(|filter| filter.id == *id) {
+                log::warn!(
+                    "Filter {} is not in the {}, skipping",
+                    id,
+                    if scanner { "scanner" } else { "account" }
+                );
+            }
+        }
+    }
+
+    log::info!("Syncing pick history of {} filters", filters.len());
+
+    let synced_at = Utc::now();
+    let mut picks = 0;
+    let mut errors = 0;
+
+    for filter in &filters {
+        let result = client.fetch_picks(filter.id).and_then(|fetched| {
+            pick_service::record(filter.id, live, &fetched, synced_at).map(|_| fetched.len())
+        });
+
+        match result {
+            Ok(count) => {
+                log::debug!("Filter {} - synced {} picks", filter.id, count);
+                picks += count;
+            }
+            Err(CliError::NotLoggedIn) => return Err(CliError::NotLoggedIn),
+            Err(CliError::SessionExpired) => return Err(CliError::SessionExpired),
+            Err(e) => {
+                log::error!("Failed to sync picks of filter {}: {}", filter.id, e);
+                errors += 1;
+            }
+        }
+    }
+
+    log::info!(
+        "Sync completed. Filters: {}, Picks: {}, Errors: {}",
+        filters.len() - errors,
+        picks,
+        errors
+    );
+
+    if errors > 0 {
+        return Err(CliError::PartialFailure {
+            failed: errors,
+            total: filters.len(),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::protection_service,
+    utils::{filesystem, paths},
+};
+
+fn find_filters(ids: &[i32], live: bool) -> Result<Vec<Filter>, CliError> {
+    let filters: Vec<Filter> = filesystem::load_data(paths::get_existing_path(live).to_string())?;
+
+    Ok(filters
+        .into_iter()
+        .filter(|filter| ids.contains(&filter.id))
+        .collect())
+}
+
+pub fn add(ids: &[i32], fingerprint: bool, live: bool) -> Result<(), CliError> {
+    let mut list = protection_service::load()?;
+
+    if fingerprint {
+        let filters = find_filters(ids, live)?;
+
+        for id in ids
+            .iter()
+            .filter(|id| !filters.iter().any(|f| f.id == **id))
+        {
+            log::warn!("Filter {} not found, protecting by ID only", id);
+            list.ids.insert(*id);
+        }
+
+        for filter in filters {
+            log::info!(
+                "Protecting filter {} by fingerprint {}",
+                filter.id,
+                filter.fingerprint()
+            );
+            list.fingerprints.insert(filter.fingerprint());
+        }
+    } else {
+        for id in ids {
+            log::info!("Protecting filter {}", id);
+            list.ids.insert(*id);
+        }
+    }
+
+    protection_service::save(&list)
+}
+
+pub fn remove(ids: &[i32], live: bool) -> Result<(), CliError> {
+    let mut list = protection_service::load()?;
+
+    for id in ids {
+        if list.ids.remove(id) {
+            log::info!("Filter {} is no longer protected", id);
+        }
+    }
+
+    if !list.fingerprints.is_empty() {
+        for filter in find_filters(ids, live)? {
+            if list.fingerprints.remove(&filter.fingerprint()) {
+                log::info!(
+                    "Fingerprint {} of filter {} is no longer protected",
+                    filter.fingerprint(),
+                    filter.id
+                );
+            }
+        }
+    }
+
+    protection_service::save(&list)
+}
+
+pub fn list() -> Result<(), CliError> {
+    let list = protection_service::load()?;
+
+    if list.ids.is_empty() && list.fingerprints.is_empty() {
+        log::info!("No protected filters");
+        return Ok(());
+    }
+
+    for id in &list.ids {
+        println!("ID {}", id);
+    }
+
+    for fingerprint in &list.fingerprints {
+        println!("Fingerprint {}", fingerprint);
+    }
+
+    Ok(())
+}
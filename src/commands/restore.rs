@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        api_client::ApiClient,
+        backup_service,
+        snapshot_service::{self, SnapshotKind},
+    },
+    utils::{command, prompt},
+};
+
+pub fn list() -> Result<(), CliError> {
+    let backups = backup_service::list()?;
+
+    if backups.is_empty() {
+        log::info!("No backups found");
+        return Ok(());
+    }
+
+    for backup in backups {
+        println!(
+            "{}  {} filters  before '{}'",
+            backup.id, backup.count, backup.command
+        );
+    }
+
+    Ok(())
+}
+
+pub fn run(id: &str, dry_run: bool, yes: bool) -> Result<(), CliError> {
+    let (manifest, backup) = backup_service::load(id)?;
+    let live = manifest.live;
+
+    log::info!(
+        "Restoring backup {} taken before '{}' ({} filters)",
+        manifest.id,
+        manifest.command,
+        manifest.count
+    );
+
+    // Compare against what is on the server right now, not a cached snapshot.
+    // It is only stored once the restore goes ahead, so a dry run changes nothing.
+    let client = ApiClient::new(live);
+    let current = snapshot_service::fetch_filters(&client, SnapshotKind::Existing)?;
+    let current_by_id: HashMap<i32, &Filter> = current.iter().map(|f| (f.id, f)).collect();
+
+    let mut to_import = Vec::new();
+    let mut to_rename = Vec::new();
+
+    for filter in &backup {
+        match current_by_id.get(&filter.id) {
+            Some(existing) if existing.name != filter.name => {
+                println!(
+                    "Filter {} was renamed '{}' -> '{}', reverting",
+                    filter.id, filter.name, existing.name
+                );
+                to_rename.push((*existing, filter.name.as_str()));
+            }
+            Some(_) => {}
+            None => match filter.source_id {
+                Some(source_id) if current.iter().any(|f| f.source_id == Some(source_id)) => {
+                    log::info!(
+                        "Filter {} was deleted but source {} is already imported again",
+                        filter.id,
+                        source_id
+                    );
+                }
+                Some(source_id) => {
+                    println!(
+                        "Filter {} was deleted, re-importing source {}",
+                        filter.id, source_id
+                    );
+                    to_import.push((filter, source_id));
+                }
+                None => log::warn!(
+                    "Filter {} was deleted but has no source ID to re-import from",
+                    filter.id
+                ),
+            },
+        }
+    }
+
+    let added = current
+        .iter()
+        .filter(|f| !backup.iter().any(|b| b.id == f.id))
+        .count();
+    if added > 0 {
+        log::info!("{} filters were added since the backup and are kept", added);
+    }
+
+    if to_import.is_empty() && to_rename.is_empty() {
+        log::info!("Nothing to restore");
+        return Ok(());
+    }
+
+    if dry_run {
+        log::info!(
+            "Dry run completed. Would re-import {} and rename {} filters",
+            to_import.len(),
+            to_rename.len()
+        );
+        return Ok(());
+    }
+
+    if !yes
+        && !prompt::confirm(&format!(
+            "Re-import {} and rename {} filters?",
+            to_import.len(),
+            to_rename.len()
+        ))?
+    {
+        log::info!("Aborted, nothing was restored");
+        return Ok(());
+    }
+
+    snapshot_service::store_snapshot(&current, SnapshotKind::Existing, live)?;
+    backup_service::create(live, "restore")?;
+
+    let mut restored = 0;
+    let mut failures = 0;
+
+    for (filter, source_id) in to_import {
+        match command::import_filter(&client, source_id, live) {
+            Ok(imported) => {
+                println!("Re-imported filter {} as {}", filter.id, imported.id);
+
+                // The copy is named after the source, not the backed-up filter
+                if imported.name != filter.name {
+                    if let Err(err) = command::rename_filter(&client, &imported, &filter.name, live)
+                    {
+                        log::error!(
+                            "Re-imported filter {} as {} but failed to rename it to '{}': {}",
+                            filter.id,
+                            imported.id,
+                            filter.name,
+                            err
+                        );
+                        failures += 1;
+                        continue;
+                    }
+                }
+
+                restored += 1;
+            }
+            Err(err) => {
+                log::error!("Failed to re-import filter {}: {}", filter.id, err);
+                failures += 1;
+            }
+        }
+    }
+
+    for (filter, name) in to_rename {
+        match command::rename_filter(&client, filter, name, live) {
+            Ok(_) => {
+                println!("Renamed filter {} back to '{}'", filter.id, name);
+                restored += 1;
+            }
+            Err(err) => {
+                log::error!("Failed to rename filter {}: {}", filter.id, err);
+                failures += 1;
+            }
+        }
+    }
+
+    log::info!(
+        "Restore completed. Restored: {}, Failures: {}",
+        restored,
+        failures
+    );
+
+    if failures > 0 {
+        return Err(CliError::PartialFailure {
+            failed: failures,
+            total: restored + failures,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use crate::{
+    errors::CliError,
+    services::{
+        metrics_service::{self, MetricPoint},
+        report_service::{self, OutputFormat, ReportRow},
+    },
+    utils::time,
+};
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub struct TrendOptions {
+    pub id: i32,
+    pub live: bool,
+    pub since: Option<String>,
+    pub format: Option<OutputFormat>,
+    pub output: Option<PathBuf>,
+}
+
+impl ReportRow for MetricPoint {
+    fn columns(_rows: &[Self]) -> Vec<&'static str> {
+        vec!["fetched_at", "id", "name", "roi", "success_rate", "picks"]
+    }
+
+    fn cells(&self, _columns: &[&str]) -> Vec<String> {
+        vec![
+            self.fetched_at.format("%Y-%m-%d %H:%M").to_string(),
+            self.id.to_string(),
+            self.name.clone(),
+            format!("{:.2}", self.roi),
+            format!("{:.2}", self.success_rate),
+            self.picks.to_string(),
+        ]
+    }
+}
+
+/// Scales the values between their minimum and maximum onto block characters.
+fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            if range <= f64::EPSILON {
+                SPARK_LEVELS[SPARK_LEVELS.len() / 2]
+            } else {
+                let level = ((value - min) / range * (SPARK_LEVELS.len() - 1) as f64).round();
+                SPARK_LEVELS[level as usize]
+            }
+        })
+        .collect()
+}
+
+fn print_sparkline(label: &str, values: &[f64], precision: usize) {
+    if let (Some(first), Some(last)) = (values.first(), values.last()) {
+        println!(
+            "{:<14}{}  {:.p$} -> {:.p$} ({:+.p$})",
+            label,
+            sparkline(values),
+            first,
+            last,
+            last - first,
+            p = precision
+        );
+    }
+}
+
+pub fn run(options: TrendOptions) -> Result<(), CliError> {
+    let since = options
+        .since
+        .as_deref()
+        .map(time::parse_point_in_time)
+        .transpose()?;
+
+    let points = metrics_service::series(options.id, options.live, since)?;
+
+    if points.is_empty() {
+        log::info!(
+            "No metrics recorded for {} filter {}, they are stored whenever your filters are fetched",
+            if options.live { "live" } else { "pre-match" },
+            options.id
+        );
+        return Ok(());
+    }
+
+    log::info!("Found {} data points", points.len());
+
+    if let Some(format) = options.format {
+        return report_service::emit(&points, format, options.output.as_deref());
+    }
+
+    report_service::emit(&points, OutputFormat::Table, None)?;
+    println!();
+
+    let series = |metric: fn(&MetricPoint) -> f64| points.iter().map(metric).collect::<Vec<_>>();
+    print_sparkline("ROI", &series(|p| p.roi as f64), 2);
+    print_sparkline("Success Rate", &series(|p| p.success_rate as f64), 2);
+    print_sparkline("Picks", &series(|p| p.picks as f64), 0);
+
+    Ok(())
+}
@@ -12,9 +12,6 @@ pub enum CliError {
     #[error("Failed to parse JSON: {0}")]
     JSONError(#[from] serde_json::Error),
 
-    #[error("Command execution failed: {0}")]
-    CommandFailed(String),
-
     #[error("Failed to open URL: {0}")]
     BrowserError(String),
 
@@ -23,4 +20,84 @@ pub enum CliError {
 
     #[error("Filter deletion failed: {0}")]
     DeletionError(String),
+
+    #[error("Filter rename failed: {0}")]
+    RenameError(String),
+
+    #[error("Network request failed: {0}")]
+    NetworkError(String),
+
+    #[error("API request failed with status {status}: {message}")]
+    ApiError { status: u16, message: String },
+
+    #[error("Authentication failed: {0}")]
+    AuthError(String),
+
+    #[error("Not logged in, run `login` first")]
+    NotLoggedIn,
+
+    #[error("Session expired, run `login` to sign in again")]
+    SessionExpired,
+
+    #[error("No snapshot available: {0}")]
+    SnapshotUnavailable(String),
+
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
+    #[error("Invalid expression: {0}")]
+    ExpressionError(String),
+
+    #[error("Backup not found: {0}")]
+    BackupNotFound(String),
+
+    #[error("Journal error: {0}")]
+    JournalError(String),
+
+    #[error("Invalid dataset: {0}")]
+    DatasetError(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+
+    #[error("{failed} of {total} operations failed")]
+    PartialFailure { failed: usize, total: usize },
+}
+
+/// Process exit codes, one per error category so that scripts can tell
+/// failures apart. Argument errors exit with 2 from clap itself.
+pub mod exit_code {
+    pub const OTHER: u8 = 1;
+    pub const CONFIG: u8 = 3;
+    pub const IO: u8 = 4;
+    pub const PARSE: u8 = 5;
+    pub const NETWORK: u8 = 6;
+    pub const AUTH: u8 = 7;
+    pub const PARTIAL_FAILURE: u8 = 8;
+    pub const NOT_FOUND: u8 = 9;
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::IoError(_) | CliError::DatabaseError(_) => exit_code::IO,
+            CliError::UnsupportedFormat(_) | CliError::JSONError(_) | CliError::DatasetError(_) => {
+                exit_code::PARSE
+            }
+            CliError::NetworkError(_)
+            | CliError::ApiError { .. }
+            | CliError::ImportError(_)
+            | CliError::DeletionError(_)
+            | CliError::RenameError(_) => exit_code::NETWORK,
+            CliError::AuthError(_) | CliError::NotLoggedIn | CliError::SessionExpired => {
+                exit_code::AUTH
+            }
+            CliError::ConfigError(_) | CliError::ExpressionError(_) => exit_code::CONFIG,
+            CliError::PartialFailure { .. } => exit_code::PARTIAL_FAILURE,
+            CliError::SnapshotUnavailable(_)
+            | CliError::BackupNotFound(_)
+            | CliError::JournalError(_) => exit_code::NOT_FOUND,
+            CliError::BrowserError(_) => exit_code::OTHER,
+        }
+    }
 }
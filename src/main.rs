@@ -1,37 +1,139 @@
 mod commands {
     pub mod analyze;
+    pub mod auth;
+    pub mod backtest;
     pub mod cleanup;
+    pub mod config;
+    pub mod correlate;
     pub mod dedup;
+    pub mod diff;
+    pub mod history;
     pub mod outcomes;
+    pub mod picks;
+    pub mod protect;
+    pub mod restore;
+    pub mod trend;
 }
 
 mod models {
+    pub mod cleanup;
     pub mod filter;
     pub mod filter_traits;
+    pub mod market;
+    pub mod match_data;
+    pub mod pick;
+    pub mod quotas;
+    pub mod risk;
+    pub mod rule_types;
+    pub mod scoring;
 }
 
 mod services {
+    pub mod api_client;
+    pub mod backtest_service;
+    pub mod backup_service;
+    pub mod correlation_service;
+    pub mod criteria_service;
+    pub mod diff_service;
     pub mod filter_service;
+    pub mod journal_service;
+    pub mod metrics_service;
+    pub mod pick_service;
+    pub mod protection_service;
+    pub mod report_service;
+    pub mod similarity_service;
+    pub mod snapshot_service;
 }
 
 mod utils {
     pub mod command;
     pub mod config;
+    pub mod credentials;
     pub mod filesystem;
     pub mod logging;
     pub mod paths;
+    pub mod prompt;
+    pub mod settings;
+    pub mod time;
 }
 
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 use commands::cleanup;
+use services::snapshot_service::{self, CachePolicy, SnapshotKind};
+use std::process::ExitCode;
 use utils::logging;
 mod errors;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct CLI {
+struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[arg(long, global = true, help = "Uses cached snapshots without fetching")]
+    offline: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "offline",
+        help = "Fetches fresh snapshots regardless of their age"
+    )]
+    refresh: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "BETMINES_CACHE_TTL",
+        default_value_t = 60,
+        value_parser = clap::value_parser!(i64).range(0..=525_600),
+        help = "Minutes before a cached snapshot is considered stale, at most a year"
+    )]
+    ttl: i64,
+
+    #[arg(
+        long,
+        global = true,
+        env = "BETMINES_PROFILE",
+        help = "Applies a named [profiles.<name>] section from config"
+    )]
+    profile: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "BETMINES_CONFIG",
+        help = "Reads this config file instead of ./betmines.toml"
+    )]
+    config: Option<std::path::PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        conflicts_with = "verbose",
+        help = "Only prints errors besides the results"
+    )]
+    quiet: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = ArgAction::Count,
+        help = "Shows more detail: -v for verbose results, -vv for debug logs"
+    )]
+    verbose: u8,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "BETMINES_LOG_FORMAT",
+        default_value = "text",
+        help = "Format of diagnostic messages on stderr"
+    )]
+    log_format: logging::LogFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -43,6 +145,61 @@ enum Commands {
 
         #[arg(short, long, help = "Treats file as live filter data")]
         live: bool,
+
+        #[arg(
+            long,
+            help = "Also reports filters whose picks are a subset of another's"
+        )]
+        subsumption: bool,
+
+        #[arg(long, help = "Also reports clusters of near-identical filters")]
+        fuzzy: bool,
+
+        #[arg(
+            long,
+            default_value_t = 0.9,
+            requires = "fuzzy",
+            help = "Minimum similarity (0-1) for --fuzzy clusters"
+        )]
+        threshold: f64,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Deletes duplicates, keeping one filter per group"
+        )]
+        resolve: Option<commands::dedup::ResolveStrategy>,
+
+        #[arg(
+            short,
+            long,
+            requires = "resolve",
+            help = "Run without making actual changes"
+        )]
+        dry_run: bool,
+
+        #[arg(
+            short,
+            long,
+            requires = "resolve",
+            help = "Skips the confirmation prompt"
+        )]
+        yes: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Prints machine-readable output in this format"
+        )]
+        format: Option<services::report_service::OutputFormat>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "format",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
     },
 
     #[command(about = "Renames filters' serviceNames based on their desiredOutcome values")]
@@ -58,6 +215,68 @@ enum Commands {
     Cleanup {
         #[arg(short, long, help = "Evaluates live filters")]
         live: bool,
+
+        #[arg(short, long, help = "Run without making actual changes")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Skips the confirmation prompt")]
+        yes: bool,
+
+        #[arg(long, help = "Deletes filters with an ROI below this percentage")]
+        roi_below: Option<f32>,
+
+        #[arg(long, help = "Only considers filters with more picks than this")]
+        picks_above: Option<u32>,
+
+        #[arg(
+            long,
+            help = "Also deletes filters whose recent picks lose money, using recorded fetches"
+        )]
+        decay: bool,
+
+        #[arg(
+            long,
+            requires = "decay",
+            help = "Start of the recent window as a date or age, e.g. 21d"
+        )]
+        decay_since: Option<String>,
+
+        #[arg(
+            long,
+            requires = "decay",
+            help = "Deletes filters with a recent-window ROI below this percentage"
+        )]
+        decay_roi_below: Option<f32>,
+
+        #[arg(
+            long,
+            help = "Deletes filters whose synced picks drew down more units than this"
+        )]
+        max_drawdown: Option<f64>,
+
+        #[arg(
+            long,
+            help = "Deletes filters whose synced picks lost more times in a row than this"
+        )]
+        max_losing_streak: Option<u32>,
+
+        #[arg(
+            long,
+            help = "Deletes filters whose profit per pick varies more than this, in units"
+        )]
+        max_volatility: Option<f64>,
+
+        #[arg(
+            long,
+            help = "Deletes filters that took more picks than this to recover a drawdown"
+        )]
+        max_recovery: Option<u32>,
+    },
+
+    #[command(about = "Manages filters that cleanup must never delete")]
+    Protect {
+        #[command(subcommand)]
+        action: ProtectAction,
     },
 
     #[command(about = "Analyzes a JSON file containing filter data and outputs the best filters.")]
@@ -87,21 +306,328 @@ enum Commands {
         )]
         offset: usize,
 
-        #[arg(short, long, help = "Prints verbose output")]
-        verbose: bool,
+        #[arg(
+            long,
+            default_value = utils::settings::DEFAULT_SCORER,
+            help = "Scoring profile used to rank filters (default, wilson or one from config)"
+        )]
+        scorer: String,
+
+        #[arg(
+            long = "where",
+            value_name = "EXPR",
+            help = "Criteria expression filters must match, e.g. \"roi >= 35 && picks >= 20\""
+        )]
+        where_expr: Option<String>,
+
+        #[arg(
+            long,
+            default_value = utils::settings::DEFAULT_CRITERIA,
+            conflicts_with = "where_expr",
+            help = "Named criteria set from config"
+        )]
+        criteria: String,
+
+        #[arg(
+            long,
+            help = "Selects at most this many filters per outcome, counting existing ones"
+        )]
+        max_per_outcome: Option<u32>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value = "top",
+            help = "How filters are picked from the ranking"
+        )]
+        select: commands::analyze::SelectionMode,
+
+        #[arg(
+            long,
+            default_value_t = 0.7,
+            help = "Weight of score against rule diversity for --select mmr, from 0 to 1"
+        )]
+        lambda: f64,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Prints machine-readable output in this format"
+        )]
+        format: Option<services::report_service::OutputFormat>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "format",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
     },
+
+    #[command(about = "Lists backups taken before destructive commands")]
+    Backups,
+
+    #[command(about = "Restores deleted and renamed filters from a backup")]
+    Restore {
+        #[arg(help = "Backup ID as shown by `backups`")]
+        backup_id: String,
+
+        #[arg(short, long, help = "Run without making actual changes")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Skips the confirmation prompt")]
+        yes: bool,
+    },
+
+    #[command(about = "Compares two snapshots of the account's filters")]
+    Diff {
+        #[arg(help = "Older snapshot, as a file or archived snapshot ID")]
+        before: Option<String>,
+
+        #[arg(help = "Newer snapshot, defaults to the current one")]
+        after: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "before",
+            help = "Compares against the newest archived fetch before this date or age, e.g. 7d"
+        )]
+        since: Option<String>,
+
+        #[arg(short, long, help = "Compares live filters")]
+        live: bool,
+
+        #[arg(long, value_enum, default_value = "table", help = "Output format")]
+        format: services::report_service::OutputFormat,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(about = "Lists archived snapshots of the account's filters")]
+    Snapshots {
+        #[arg(short, long, help = "Lists live snapshots")]
+        live: bool,
+    },
+
+    #[command(about = "Shows how a filter's metrics evolved across fetches")]
+    Trend {
+        #[arg(help = "Filter ID")]
+        id: i32,
+
+        #[arg(short, long, help = "Looks the filter up in live data")]
+        live: bool,
+
+        #[arg(
+            long,
+            help = "Only data points since a date or age, e.g. 2024-01-31 or 30d"
+        )]
+        since: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Prints machine-readable output in this format"
+        )]
+        format: Option<services::report_service::OutputFormat>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "format",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(
+        about = "Shows the journal of imports, deletions and renames",
+        args_conflicts_with_subcommands = true
+    )]
+    History {
+        #[command(subcommand)]
+        command: Option<HistoryAction>,
+
+        #[arg(long, help = "Only entries since a date or age, e.g. 2024-01-31 or 7d")]
+        since: Option<String>,
+
+        #[arg(long, value_enum, help = "Only entries of this action")]
+        action: Option<services::journal_service::Action>,
+
+        #[arg(long, help = "Only entries touching this filter ID")]
+        id: Option<i32>,
+    },
+
+    #[command(about = "Reports pick overlap and result correlation between filters")]
+    Correlate {
+        #[arg(help = "Only compares these filter IDs")]
+        ids: Vec<i32>,
+
+        #[arg(short, long, help = "Compares live filters")]
+        live: bool,
+
+        #[arg(
+            long,
+            default_value_t = 1,
+            help = "Only reports pairs sharing at least this many matches"
+        )]
+        min_shared: usize,
+
+        #[arg(long, value_enum, default_value = "table", help = "Output format")]
+        format: services::report_service::OutputFormat,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(about = "Replays filters' rules over a local match dataset")]
+    Backtest {
+        #[arg(help = "Match dataset as a .json or .csv file")]
+        dataset: std::path::PathBuf,
+
+        #[arg(help = "Only backtests these filter IDs")]
+        ids: Vec<i32>,
+
+        #[arg(
+            short,
+            long,
+            help = "Filters to backtest instead of the account's, as a JSON file"
+        )]
+        filename: Option<String>,
+
+        #[arg(short, long, help = "Backtests live filters")]
+        live: bool,
+
+        #[arg(long, value_enum, default_value = "table", help = "Output format")]
+        format: services::report_service::OutputFormat,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Writes the output to a file instead of stdout"
+        )]
+        output: Option<std::path::PathBuf>,
+    },
+
+    #[command(about = "Reverses a journal entry")]
+    Undo {
+        #[arg(help = "Entry number as shown by `history`")]
+        entry: u64,
+
+        #[arg(short, long, help = "Run without making actual changes")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Skips the confirmation prompt")]
+        yes: bool,
+    },
+
+    #[command(about = "Inspects the effective configuration")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    #[command(about = "Logs in to betmines and stores the session token")]
+    Login {
+        #[arg(short, long, help = "Account username or email")]
+        username: String,
+
+        #[arg(long, help = "Reads the password from stdin instead of prompting")]
+        password_stdin: bool,
+    },
+
+    #[command(about = "Removes the stored session token")]
+    Logout,
+
+    #[command(about = "Shows the account the stored session belongs to")]
+    Whoami,
+}
+
+#[derive(Subcommand, Debug)]
+enum ProtectAction {
+    #[command(about = "Protects filters by ID")]
+    Add {
+        #[arg(required = true)]
+        ids: Vec<i32>,
+
+        #[arg(long, help = "Protects every filter with the same outcome and rules")]
+        fingerprint: bool,
+
+        #[arg(short, long, help = "Looks the filters up in live data")]
+        live: bool,
+    },
+
+    #[command(about = "Removes filters from the protected list")]
+    Remove {
+        #[arg(required = true)]
+        ids: Vec<i32>,
+
+        #[arg(short, long, help = "Looks the filters up in live data")]
+        live: bool,
+    },
+
+    #[command(about = "Lists protected IDs and fingerprints")]
+    List,
 }
 
-fn main() {
-    logging::setup_logging();
+#[derive(Subcommand, Debug)]
+enum HistoryAction {
+    #[command(about = "Downloads the pick history of the account's filters")]
+    Sync {
+        #[arg(help = "Only syncs these filter IDs")]
+        ids: Vec<i32>,
 
-    if let Err(err) = utils::command::fetch_filters() {
-        log::error!("Failed to fetch filters: {}", err);
+        #[arg(short, long, help = "Syncs live filters")]
+        live: bool,
+
+        #[arg(long, help = "Syncs the scanner's filters instead of the account's")]
+        scanner: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    #[command(about = "Prints the effective values after all layers are applied")]
+    Show,
+}
+
+/// Logs a failed command and maps it to its exit code.
+fn report(result: Result<(), errors::CliError>, context: &str) -> u8 {
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            log::error!("{}: {}", context, err);
+            err.exit_code()
+        }
     }
+}
 
-    let cli = CLI::parse();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    match &cli.command {
+    logging::setup_logging(cli.quiet, cli.verbose, cli.log_format);
+
+    if let Err(err) = utils::settings::init(cli.config.as_deref(), cli.profile.as_deref()) {
+        log::error!("Failed to load configuration: {}", err);
+        return ExitCode::from(err.exit_code());
+    }
+
+    let cache = CachePolicy {
+        offline: cli.offline,
+        refresh: cli.refresh,
+        ttl: chrono::Duration::minutes(cli.ttl),
+    };
+
+    let code = match &cli.command {
         Commands::Outcomes { live, dry_run } => {
             log::info!(
                 "Running outcomes renaming for {} filters{}",
@@ -109,9 +635,11 @@ fn main() {
                 if *dry_run { " (dry-run)" } else { "" },
             );
 
-            if let Err(err) = commands::outcomes::run(*live, *dry_run) {
-                log::error!("Failed to run outcomes command: {}", err);
-            }
+            report(
+                snapshot_service::prepare(&[SnapshotKind::Existing], *live, &cache)
+                    .and_then(|_| commands::outcomes::run(*live, *dry_run)),
+                "Failed to run outcomes command",
+            )
         }
         Commands::Analyze {
             filename,
@@ -121,7 +649,14 @@ fn main() {
             live,
             offset,
             autoimport,
-            verbose,
+            scorer,
+            where_expr,
+            criteria,
+            max_per_outcome,
+            select,
+            lambda,
+            format,
+            output,
         } => {
             let file_path = filename
                 .clone()
@@ -129,39 +664,297 @@ fn main() {
 
             log::info!("Running analysis on {} for {} filters", file_path, count);
 
-            if let Err(err) = commands::analyze::run(
-                file_path,
-                existing,
-                *count,
-                *open,
-                *live,
-                *offset,
-                *autoimport,
-                *verbose,
-            ) {
-                log::error!("Failed to run analysis: {}", err);
+            let mut snapshots = Vec::new();
+            if filename.is_none() {
+                snapshots.push(SnapshotKind::Scanner);
             }
+            if existing.is_none() {
+                snapshots.push(SnapshotKind::Existing);
+            }
+
+            report(
+                snapshot_service::prepare(&snapshots, *live, &cache).and_then(|_| {
+                    commands::analyze::run(commands::analyze::AnalyzeOptions {
+                        filename: file_path,
+                        existing: existing.clone(),
+                        count: *count,
+                        open: *open,
+                        live: *live,
+                        offset: *offset,
+                        autoimport: *autoimport,
+                        verbose: cli.verbose > 0,
+                        scorer: scorer.clone(),
+                        where_expr: where_expr.clone(),
+                        criteria: criteria.clone(),
+                        max_per_outcome: *max_per_outcome,
+                        select: *select,
+                        lambda: *lambda,
+                        format: *format,
+                        output: output.clone(),
+                    })
+                }),
+                "Failed to run analysis",
+            )
         }
-        Commands::Cleanup { live } => {
+        Commands::Cleanup {
+            live,
+            dry_run,
+            yes,
+            roi_below,
+            picks_above,
+            decay,
+            decay_since,
+            decay_roi_below,
+            max_drawdown,
+            max_losing_streak,
+            max_volatility,
+            max_recovery,
+        } => {
             log::info!(
                 "Running cleanup for {} filters",
                 if *live { "live" } else { "pre-match" }
             );
 
-            if let Err(err) = cleanup::run(*live) {
-                log::error!("Failed to run cleanup: {}", err);
-            }
+            report(
+                snapshot_service::prepare(&[SnapshotKind::Existing], *live, &cache).and_then(
+                    |_| {
+                        cleanup::run(cleanup::CleanupOptions {
+                            live: *live,
+                            dry_run: *dry_run,
+                            yes: *yes,
+                            roi_below: *roi_below,
+                            picks_above: *picks_above,
+                            decay: *decay,
+                            decay_since: decay_since.clone(),
+                            decay_roi_below: *decay_roi_below,
+                            max_drawdown: *max_drawdown,
+                            max_losing_streak: *max_losing_streak,
+                            max_volatility: *max_volatility,
+                            max_recovery: *max_recovery,
+                        })
+                    },
+                ),
+                "Failed to run cleanup",
+            )
         }
-        Commands::Dedup { filename, live } => {
+        Commands::Dedup {
+            filename,
+            live,
+            subsumption,
+            fuzzy,
+            threshold,
+            resolve,
+            dry_run,
+            yes,
+            format,
+            output,
+        } => {
             let file_path = filename
                 .clone()
                 .unwrap_or_else(|| utils::paths::get_existing_path(*live).to_string());
 
             log::info!("Running deduplication on {}", file_path);
 
-            if let Err(err) = commands::dedup::run(file_path, *live) {
-                log::error!("Failed to run deduplication: {}", err);
-            }
+            let snapshots: &[SnapshotKind] = if filename.is_none() {
+                &[SnapshotKind::Existing]
+            } else {
+                &[]
+            };
+
+            report(
+                snapshot_service::prepare(snapshots, *live, &cache).and_then(|_| {
+                    commands::dedup::run(commands::dedup::DedupOptions {
+                        filename: file_path,
+                        live: *live,
+                        subsumption: *subsumption,
+                        fuzzy: fuzzy.then_some(*threshold),
+                        resolve: resolve.map(|strategy| commands::dedup::ResolveOptions {
+                            strategy,
+                            dry_run: *dry_run,
+                            yes: *yes,
+                        }),
+                        format: *format,
+                        output: output.clone(),
+                    })
+                }),
+                "Failed to run deduplication",
+            )
         }
-    }
+        Commands::Protect { action } => {
+            let result = match action {
+                ProtectAction::Add {
+                    ids,
+                    fingerprint,
+                    live,
+                } => commands::protect::add(ids, *fingerprint, *live),
+                ProtectAction::Remove { ids, live } => commands::protect::remove(ids, *live),
+                ProtectAction::List => commands::protect::list(),
+            };
+
+            report(result, "Failed to update protected filters")
+        }
+        Commands::Backups => report(commands::restore::list(), "Failed to list backups"),
+        Commands::Restore {
+            backup_id,
+            dry_run,
+            yes,
+        } => report(
+            commands::restore::run(backup_id, *dry_run, *yes),
+            "Failed to restore backup",
+        ),
+        Commands::Diff {
+            before,
+            after,
+            since,
+            live,
+            format,
+            output,
+        } => {
+            let snapshots: &[SnapshotKind] = if after.is_none() {
+                &[SnapshotKind::Existing]
+            } else {
+                &[]
+            };
+
+            report(
+                snapshot_service::prepare(snapshots, *live, &cache).and_then(|_| {
+                    commands::diff::run(commands::diff::DiffOptions {
+                        before: before.clone(),
+                        after: after.clone(),
+                        since: since.clone(),
+                        live: *live,
+                        format: *format,
+                        output: output.clone(),
+                    })
+                }),
+                "Failed to compare snapshots",
+            )
+        }
+        Commands::Snapshots { live } => {
+            report(commands::diff::list(*live), "Failed to list snapshots")
+        }
+        Commands::Trend {
+            id,
+            live,
+            since,
+            format,
+            output,
+        } => report(
+            commands::trend::run(commands::trend::TrendOptions {
+                id: *id,
+                live: *live,
+                since: since.clone(),
+                format: *format,
+                output: output.clone(),
+            }),
+            "Failed to show trend",
+        ),
+        Commands::History {
+            command: Some(HistoryAction::Sync { ids, live, scanner }),
+            ..
+        } => {
+            let snapshot = if *scanner {
+                SnapshotKind::Scanner
+            } else {
+                SnapshotKind::Existing
+            };
+
+            report(
+                snapshot_service::prepare(&[snapshot], *live, &cache).and_then(|_| {
+                    commands::picks::sync(
+                        &services::api_client::ApiClient::new(*live),
+                        ids,
+                        *live,
+                        *scanner,
+                    )
+                }),
+                "Failed to sync pick history",
+            )
+        }
+        Commands::History {
+            command: None,
+            since,
+            action,
+            id,
+        } => report(
+            commands::history::list(commands::history::HistoryQuery {
+                since: since.clone(),
+                action: *action,
+                id: *id,
+            }),
+            "Failed to show history",
+        ),
+        Commands::Correlate {
+            ids,
+            live,
+            min_shared,
+            format,
+            output,
+        } => report(
+            snapshot_service::prepare(&[SnapshotKind::Existing], *live, &cache).and_then(|_| {
+                commands::correlate::run(commands::correlate::CorrelateOptions {
+                    ids: ids.clone(),
+                    live: *live,
+                    min_shared: *min_shared,
+                    format: *format,
+                    output: output.clone(),
+                })
+            }),
+            "Failed to correlate filters",
+        ),
+        Commands::Backtest {
+            dataset,
+            ids,
+            filename,
+            live,
+            format,
+            output,
+        } => {
+            let snapshots: &[SnapshotKind] = if filename.is_none() {
+                &[SnapshotKind::Existing]
+            } else {
+                &[]
+            };
+
+            report(
+                snapshot_service::prepare(snapshots, *live, &cache).and_then(|_| {
+                    commands::backtest::run(commands::backtest::BacktestOptions {
+                        dataset: dataset.clone(),
+                        ids: ids.clone(),
+                        filename: filename.clone(),
+                        live: *live,
+                        format: *format,
+                        output: output.clone(),
+                    })
+                }),
+                "Failed to run backtest",
+            )
+        }
+        Commands::Undo {
+            entry,
+            dry_run,
+            yes,
+        } => report(
+            commands::history::undo(*entry, *dry_run, *yes),
+            &format!("Failed to undo entry {}", entry),
+        ),
+        Commands::Config { action } => {
+            let result = match action {
+                ConfigAction::Show => commands::config::show(),
+            };
+
+            report(result, "Failed to show configuration")
+        }
+        Commands::Login {
+            username,
+            password_stdin,
+        } => report(
+            commands::auth::login(username, *password_stdin),
+            "Failed to log in",
+        ),
+        Commands::Logout => report(commands::auth::logout(), "Failed to log out"),
+        Commands::Whoami => report(commands::auth::whoami(), "Failed to check session"),
+    };
+
+    ExitCode::from(code)
 }
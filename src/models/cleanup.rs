@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use super::risk::RiskMetrics;
+
+/// Filters with an ROI below `roi_below` once they have more than
+/// `picks_above` picks are considered low performing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupThresholds {
+    pub roi_below: f32,
+    pub picks_above: u32,
+    pub decay: DecayThresholds,
+    pub risk: RiskThresholds,
+}
+
+impl Default for CleanupThresholds {
+    fn default() -> Self {
+        Self {
+            roi_below: 10.0,
+            picks_above: 30,
+            decay: DecayThresholds::default(),
+            risk: RiskThresholds::default(),
+        }
+    }
+}
+
+/// Filters whose picks since `since` have an ROI below `roi_below` are
+/// considered decaying, once at least `min_picks` picks were added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecayThresholds {
+    pub since: String,
+    pub roi_below: f32,
+    pub min_picks: u32,
+}
+
+impl Default for DecayThresholds {
+    fn default() -> Self {
+        Self {
+            since: "21d".into(),
+            roi_below: 0.0,
+            min_picks: 10,
+        }
+    }
+}
+
+/// Filters whose synced pick history exceeds any of the set limits are
+/// considered too risky to follow. Unset limits are not checked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskThresholds {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_drawdown: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_losing_streak: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_volatility: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_recovery: Option<u32>,
+}
+
+impl RiskThresholds {
+    pub fn is_empty(&self) -> bool {
+        self.max_drawdown.is_none()
+            && self.max_losing_streak.is_none()
+            && self.max_volatility.is_none()
+            && self.max_recovery.is_none()
+    }
+
+    /// Describes the first limit the metrics exceed, if any.
+    pub fn exceeded_by(&self, risk: &RiskMetrics) -> Option<String> {
+        if let Some(limit) = self.max_drawdown.filter(|l| risk.max_drawdown > *l) {
+            return Some(format!(
+                "drawdown {:.2}u > {:.2}u",
+                risk.max_drawdown, limit
+            ));
+        }
+
+        if let Some(limit) = self.max_losing_streak.filter(|l| risk.losing_streak > *l) {
+            return Some(format!("losing streak {} > {}", risk.losing_streak, limit));
+        }
+
+        if let Some(limit) = self.max_volatility.filter(|l| risk.volatility > *l) {
+            return Some(format!(
+                "volatility {:.2}u > {:.2}u",
+                risk.volatility, limit
+            ));
+        }
+
+        if let Some(limit) = self.max_recovery.filter(|l| risk.time_to_recover > *l) {
+            return Some(format!(
+                "recovery {} picks > {}",
+                risk.time_to_recover, limit
+            ));
+        }
+
+        None
+    }
+}
+
+/// Performance of only the picks a filter made after a reference fetch.
+#[derive(Debug, Clone, Copy)]
+pub struct RecentWindow {
+    pub picks: u32,
+    pub roi: f64,
+}
@@ -1,25 +1,66 @@
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
-use super::filter_traits::{FilterScoring, FilterValidation};
+use super::{
+    cleanup::{CleanupThresholds, RecentWindow},
+    filter_traits::{FilterScoring, FilterValidation},
+    market::Market,
+    risk::RiskMetrics,
+    rule_types::{ComparatorType, Probability, RuleType, Target, TimeWindow, TrendStats},
+    scoring::{self, ScoreBreakdown, ScoringMethod, ScoringProfile},
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Rule {
-    pub target: Option<String>,
-    pub probability: Option<String>,
-    pub time: Option<String>,
+    pub target: Option<Target>,
+    pub probability: Option<Probability>,
+    pub time: Option<TimeWindow>,
 
     #[serde(rename = "valueToCompare")]
     pub value_to_compare: Option<f32>,
 
     #[serde(rename = "trendStats")]
-    pub trend_stats: Option<String>,
+    pub trend_stats: Option<TrendStats>,
 
     #[serde(rename = "comparatorType")]
-    pub comparator_type: Option<String>,
+    pub comparator_type: Option<ComparatorType>,
 
     #[serde(rename = "type")]
-    pub rule_type: Option<String>,
+    pub rule_type: Option<RuleType>,
+}
+
+impl Rule {
+    /// Whether both rules look at the same statistic in the same way,
+    /// ignoring the threshold.
+    pub fn same_condition(&self, other: &Rule) -> bool {
+        self.comparator_type == other.comparator_type
+            && self.target == other.target
+            && self.rule_type == other.rule_type
+            && self.trend_stats == other.trend_stats
+            && self.probability == other.probability
+            && self.time == other.time
+    }
+
+    /// Whether every match passing this rule also passes `other`.
+    pub fn is_at_least_as_strict_as(&self, other: &Rule) -> bool {
+        if !self.same_condition(other) {
+            return false;
+        }
+
+        let (value, other_value) = match (self.value_to_compare, other.value_to_compare) {
+            (Some(value), Some(other_value)) => (value, other_value),
+            (None, None) => return true,
+            _ => return false,
+        };
+
+        match self.comparator_type {
+            Some(ComparatorType::GreaterThan | ComparatorType::GreaterOrEqual) => {
+                value >= other_value
+            }
+            Some(ComparatorType::LessThan | ComparatorType::LessOrEqual) => value <= other_value,
+            _ => value.to_bits() == other_value.to_bits(),
+        }
+    }
 }
 
 impl Eq for Rule {}
@@ -85,41 +126,109 @@ pub struct Filter {
     pub success_rate: f32,
 
     #[serde(rename = "desiredOutcome")]
-    pub desired_outcome: Option<String>,
+    pub desired_outcome: Option<Market>,
+
+    /// The scanner filter this one was copied from
+    #[serde(
+        rename = "originalFilterId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_id: Option<i32>,
+
+    /// Risk metrics from the synced pick history, when there is one
+    #[serde(skip)]
+    pub risk: Option<RiskMetrics>,
 }
 
 impl FilterValidation for Filter {
-    fn is_low_performing(&self) -> bool {
-        self.roi < 10.0 && self.total_picks > 30
+    fn is_low_performing(&self, thresholds: &CleanupThresholds) -> bool {
+        self.roi < thresholds.roi_below && self.total_picks > thresholds.picks_above
     }
+}
 
-    fn is_valid(&self) -> bool {
-        self.roi >= 40.0
-            && self.total_picks >= 15
-            && self.desired_outcome.as_ref().map_or(false, |value| {
-                !value.starts_with("CO") && !value.starts_with("CU")
-            })
+impl FilterScoring for Filter {
+    fn score_with(&self, profile: &ScoringProfile) -> ScoreBreakdown {
+        if (self.roi as f64) < profile.min_roi {
+            return ScoreBreakdown::default();
+        }
+
+        let (roi, success_rate) = match profile.method {
+            ScoringMethod::Weighted => (self.roi as f64, self.success_rate as f64),
+            ScoringMethod::Wilson => (
+                self.roi_interval(profile.z).0,
+                self.success_rate_interval(profile.z).0,
+            ),
+        };
+
+        ScoreBreakdown {
+            roi: profile.roi_weight * roi,
+            success_rate: profile.success_rate_weight * success_rate,
+            picks: profile.picks_weight * profile.picks.apply(self.total_picks),
+        }
     }
 }
 
-impl FilterScoring for Filter {
-    fn get_score(&self) -> f64 {
-        // roi
-        let roi_weight = 0.8;
+impl Filter {
+    /// ROI of the picks made since an earlier fetch with the given cumulative
+    /// ROI and pick count, assuming equal stakes per pick. `None` when no
+    /// picks were added since.
+    pub fn recent_window(&self, baseline_roi: f32, baseline_picks: u32) -> Option<RecentWindow> {
+        let picks = self
+            .total_picks
+            .checked_sub(baseline_picks)
+            .filter(|p| *p > 0)?;
 
-        // success rate
-        let sr_weight = 0.3;
+        let profit = self.roi as f64 * self.total_picks as f64;
+        let baseline_profit = baseline_roi as f64 * baseline_picks as f64;
 
-        // picks
-        let ps_weight = 0.1;
+        Some(RecentWindow {
+            picks,
+            roi: (profit - baseline_profit) / picks as f64,
+        })
+    }
 
-        if self.roi < 0.0 {
-            return 0.0;
-        }
+    /// A stable identifier for what the filter picks: its outcome and rules,
+    /// independent of its ID, name or metrics. Re-imported copies share it.
+    pub fn fingerprint(&self) -> String {
+        let mut rules = self.rules.clone();
+        rules.sort();
 
-        roi_weight * self.roi as f64
-            + sr_weight * self.success_rate as f64
-            + ps_weight * self.total_picks as f64
+        let canonical = serde_json::to_string(&(&self.desired_outcome, &rules))
+            .expect("filters always serialize");
+
+        // FNV-1a, which unlike the std hasher is stable across releases
+        let hash = canonical.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+        format!("{:016x}", hash)
+    }
+
+    pub fn success_rate_interval(&self, z: f64) -> (f64, f64) {
+        scoring::wilson_interval(self.success_rate as f64, self.total_picks, z)
+    }
+
+    pub fn roi_interval(&self, z: f64) -> (f64, f64) {
+        scoring::roi_interval(
+            self.roi as f64,
+            self.success_rate as f64,
+            self.total_picks,
+            z,
+        )
+    }
+
+    /// Whether every pick of `other` is also a pick of this filter, i.e. both
+    /// bet on the same outcome and each of this filter's rules is implied by
+    /// a rule of `other`.
+    pub fn subsumes(&self, other: &Filter) -> bool {
+        self.desired_outcome == other.desired_outcome
+            && self.rules.iter().all(|rule| {
+                other
+                    .rules
+                    .iter()
+                    .any(|other_rule| other_rule.is_at_least_as_strict_as(rule))
+            })
     }
 }
 
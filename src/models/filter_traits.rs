@@ -1,8 +1,16 @@
+use super::{
+    cleanup::CleanupThresholds,
+    scoring::{ScoreBreakdown, ScoringProfile},
+};
+
 pub trait FilterValidation {
-    fn is_low_performing(&self) -> bool;
-    fn is_valid(&self) -> bool;
+    fn is_low_performing(&self, thresholds: &CleanupThresholds) -> bool;
 }
 
 pub trait FilterScoring {
-    fn get_score(&self) -> f64;
+    fn score_with(&self, profile: &ScoringProfile) -> ScoreBreakdown;
+
+    fn get_score(&self) -> f64 {
+        self.score_with(&ScoringProfile::default()).total()
+    }
 }
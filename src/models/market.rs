@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarketCategory {
+    Result,
+    Goals,
+    Corners,
+    HalfTime,
+    Other,
+}
+
+impl MarketCategory {
+    pub const ALL: [MarketCategory; 5] = [
+        MarketCategory::Result,
+        MarketCategory::Goals,
+        MarketCategory::Corners,
+        MarketCategory::HalfTime,
+        MarketCategory::Other,
+    ];
+}
+
+impl fmt::Display for MarketCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MarketCategory::Result => "result",
+            MarketCategory::Goals => "goals",
+            MarketCategory::Corners => "corners",
+            MarketCategory::HalfTime => "half-time",
+            MarketCategory::Other => "other",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Over,
+    Under,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Team {
+    Home,
+    Away,
+}
+
+/// A goal or corner line stored in tenths, so `25` is the 2.5 line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line(pub u16);
+
+impl Line {
+    pub fn value(&self) -> f32 {
+        self.0 as f32 / 10.0
+    }
+
+    fn from_digits(digits: &str) -> Option<Self> {
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        digits.parse().ok().map(Line)
+    }
+
+    fn from_decimal(decimal: &str) -> Option<Self> {
+        let value: f32 = decimal.parse().ok()?;
+        Some(Line((value * 10.0).round() as u16))
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.0 / 10, self.0 % 10)
+    }
+}
+
+/// An outcome a filter can be set to pick, as found in `desiredOutcome`.
+///
+/// Codes that don't match the catalog are kept in `Unknown`, so a market
+/// always displays as the exact string it was parsed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Market {
+    HomeWin,
+    Draw,
+    AwayWin,
+    HomeOrAway,
+    HomeOrDraw,
+    DrawOrAway,
+    FavoriteWins,
+    FavoriteWinsAtHome,
+    UnderdogWins,
+    UnderdogWinsAtHome,
+    BothTeamsScore,
+    NoBothTeamsScore,
+    Goals { side: Side, line: Line },
+    TeamGoals { team: Team, side: Side, line: Line },
+    HalfTimeHomeWin,
+    HalfTimeDraw,
+    HalfTimeAwayWin,
+    HalfTimeBothTeamsScore,
+    HalfTimeGoals { side: Side, line: Line },
+    Corners { side: Side, line: Line },
+    GoalsSincePicked { line: Line },
+    Unknown(String),
+}
+
+impl Market {
+    pub fn parse(code: &str) -> Self {
+        match Self::parse_known(code) {
+            // Only accept the catalog spelling so display stays lossless
+            Some(market) if market.to_string() == code => market,
+            _ => Market::Unknown(code.to_string()),
+        }
+    }
+
+    fn parse_known(code: &str) -> Option<Self> {
+        let market = match code {
+            "1" => Market::HomeWin,
+            "X" => Market::Draw,
+            "2" => Market::AwayWin,
+            "12" => Market::HomeOrAway,
+            "1X" => Market::HomeOrDraw,
+            "X2" => Market::DrawOrAway,
+            "Favorite wins" => Market::FavoriteWins,
+            "Favorite wins at home" => Market::FavoriteWinsAtHome,
+            "Underdog wins" => Market::UnderdogWins,
+            "Underdog wins at home" => Market::UnderdogWinsAtHome,
+            "GG" => Market::BothTeamsScore,
+            "NG" => Market::NoBothTeamsScore,
+            "1 HT" => Market::HalfTimeHomeWin,
+            "X HT" => Market::HalfTimeDraw,
+            "2 HT" => Market::HalfTimeAwayWin,
+            "GG HT" => Market::HalfTimeBothTeamsScore,
+            _ => return Self::parse_line_market(code),
+        };
+
+        Some(market)
+    }
+
+    fn parse_line_market(code: &str) -> Option<Self> {
+        if let Some(rest) = code
+            .strip_prefix("Over ")
+            .and_then(|rest| rest.strip_suffix(" Since Picked"))
+        {
+            return Some(Market::GoalsSincePicked {
+                line: Line::from_decimal(rest)?,
+            });
+        }
+
+        if let Some(rest) = code.strip_suffix("HT") {
+            let (side, line) = parse_signed(rest)?;
+            return Some(Market::HalfTimeGoals { side, line });
+        }
+
+        if let Some(rest) = code.strip_prefix('C') {
+            let (side, line) = parse_lettered(rest)?;
+            return Some(Market::Corners { side, line });
+        }
+
+        for (prefix, team) in [("Home_", Team::Home), ("Away_", Team::Away)] {
+            if let Some(rest) = code.strip_prefix(prefix) {
+                let (side, line) = parse_lettered(rest)?;
+                return Some(Market::TeamGoals { team, side, line });
+            }
+        }
+
+        let (side, line) = parse_signed(code).or_else(|| parse_lettered(code))?;
+        Some(Market::Goals { side, line })
+    }
+
+    pub fn category(&self) -> MarketCategory {
+        match self {
+            Market::HomeWin
+            | Market::Draw
+            | Market::AwayWin
+            | Market::HomeOrAway
+            | Market::HomeOrDraw
+            | Market::DrawOrAway
+            | Market::FavoriteWins
+            | Market::FavoriteWinsAtHome
+            | Market::UnderdogWins
+            | Market::UnderdogWinsAtHome => MarketCategory::Result,
+            Market::BothTeamsScore
+            | Market::NoBothTeamsScore
+            | Market::Goals { .. }
+            | Market::TeamGoals { .. }
+            | Market::GoalsSincePicked { .. } => MarketCategory::Goals,
+            Market::HalfTimeHomeWin
+            | Market::HalfTimeDraw
+            | Market::HalfTimeAwayWin
+            | Market::HalfTimeBothTeamsScore
+            | Market::HalfTimeGoals { .. } => MarketCategory::HalfTime,
+            Market::Corners { .. } => MarketCategory::Corners,
+            // Corner codes outside the catalog, e.g. `CO9.5`, are still corners
+            Market::Unknown(code) if code.starts_with("CO") || code.starts_with("CU") => {
+                MarketCategory::Corners
+            }
+            Market::Unknown(_) => MarketCategory::Other,
+        }
+    }
+
+    /// The short tag filters are renamed to, e.g. `(+2.5)` for over 2.5 goals.
+    pub fn label(&self) -> Option<String> {
+        let label = match self {
+            Market::HomeWin => "(1)".to_string(),
+            Market::Draw => "(X)".to_string(),
+            Market::AwayWin => "(2)".to_string(),
+            Market::HomeOrAway => "(12)".to_string(),
+            Market::HomeOrDraw => "(1X)".to_string(),
+            Market::DrawOrAway => "(X2)".to_string(),
+            Market::FavoriteWins | Market::FavoriteWinsAtHome => "(Fav)".to_string(),
+            Market::UnderdogWins | Market::UnderdogWinsAtHome => "(Underdog)".to_string(),
+            Market::BothTeamsScore => "(GG)".to_string(),
+            Market::NoBothTeamsScore => "(NG)".to_string(),
+            Market::Goals { side, line } => format!("({}{})", sign(*side), line),
+            Market::TeamGoals { team, side, line } => {
+                format!("({} {}{})", team_name(*team), sign(*side), line)
+            }
+            Market::HalfTimeHomeWin => "(1HT)".to_string(),
+            Market::HalfTimeDraw => "(XHT)".to_string(),
+            Market::HalfTimeAwayWin => "(2HT)".to_string(),
+            // Both teams scoring by half-time means at least two goals
+            Market::HalfTimeBothTeamsScore => "(+1.5HT)".to_string(),
+            Market::HalfTimeGoals { side, line } => format!("({}{}HT)", sign(*side), line),
+            Market::Corners { side, line } => format!("(C{}{})", sign(*side), line),
+            Market::GoalsSincePicked { line } => format!("(+{}G)", line.0 / 10 + 1),
+            Market::Unknown(_) => return None,
+        };
+
+        Some(label)
+    }
+}
+
+fn sign(side: Side) -> char {
+    match side {
+        Side::Over => '+',
+        Side::Under => '-',
+    }
+}
+
+fn letter(side: Side) -> char {
+    match side {
+        Side::Over => 'O',
+        Side::Under => 'U',
+    }
+}
+
+fn team_name(team: Team) -> &'static str {
+    match team {
+        Team::Home => "Home",
+        Team::Away => "Away",
+    }
+}
+
+/// Parses `+0.5` / `-1.5` style lines.
+fn parse_signed(code: &str) -> Option<(Side, Line)> {
+    let (side, rest) = if let Some(rest) = code.strip_prefix('+') {
+        (Side::Over, rest)
+    } else {
+        (Side::Under, code.strip_prefix('-')?)
+    };
+
+    Some((side, Line::from_decimal(rest)?))
+}
+
+/// Parses `O25` / `U15` style lines.
+fn parse_lettered(code: &str) -> Option<(Side, Line)> {
+    let (side, rest) = if let Some(rest) = code.strip_prefix('O') {
+        (Side::Over, rest)
+    } else {
+        (Side::Under, code.strip_prefix('U')?)
+    };
+
+    Some((side, Line::from_digits(rest)?))
+}
+
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Market::HomeWin => f.write_str("1"),
+            Market::Draw => f.write_str("X"),
+            Market::AwayWin => f.write_str("2"),
+            Market::HomeOrAway => f.write_str("12"),
+            Market::HomeOrDraw => f.write_str("1X"),
+            Market::DrawOrAway => f.write_str("X2"),
+            Market::FavoriteWins => f.write_str("Favorite wins"),
+            Market::FavoriteWinsAtHome => f.write_str("Favorite wins at home"),
+            Market::UnderdogWins => f.write_str("Underdog wins"),
+            Market::UnderdogWinsAtHome => f.write_str("Underdog wins at home"),
+            Market::BothTeamsScore => f.write_str("GG"),
+            Market::NoBothTeamsScore => f.write_str("NG"),
+            // betmines spells the 0.5 line with a sign and the others with a letter
+            Market::Goals { side, line } if line.0 < 10 => write!(f, "{}{}", sign(*side), line),
+            Market::Goals { side, line } => write!(f, "{}{}", letter(*side), line.0),
+            Market::TeamGoals { team, side, line } => {
+                write!(f, "{}_{}{}", team_name(*team), letter(*side), line.0)
+            }
+            Market::HalfTimeHomeWin => f.write_str("1 HT"),
+            Market::HalfTimeDraw => f.write_str("X HT"),
+            Market::HalfTimeAwayWin => f.write_str("2 HT"),
+            Market::HalfTimeBothTeamsScore => f.write_str("GG HT"),
+            Market::HalfTimeGoals { side, line } => write!(f, "{}{}HT", sign(*side), line),
+            Market::Corners { side, line } => write!(f, "C{}{}", letter(*side), line.0),
+            Market::GoalsSincePicked { line } => write!(f, "Over {} Since Picked", line),
+            Market::Unknown(code) => f.write_str(code),
+        }
+    }
+}
+
+impl From<String> for Market {
+    fn from(code: String) -> Self {
+        Market::parse(&code)
+    }
+}
+
+impl From<Market> for String {
+    fn from(market: Market) -> Self {
+        market.to_string()
+    }
+}
+
+impl PartialEq for Market {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl Eq for Market {}
+
+impl Hash for Market {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_string().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unparseable_corner_codes_are_still_corners() {
+        for code in ["CO95", "CU105", "CO9.5", "CU", "COx"] {
+            assert_eq!(
+                Market::parse(code).category(),
+                MarketCategory::Corners,
+                "{}",
+                code
+            );
+        }
+
+        assert_eq!(Market::parse("C9").category(), MarketCategory::Other);
+        assert_eq!(Market::parse("O25").category(), MarketCategory::Goals);
+    }
+}
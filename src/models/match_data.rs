@@ -0,0 +1,380 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{
+    filter::Rule,
+    market::{Line, Market, Side, Team},
+    pick::PickResult,
+    rule_types::{RuleType, Target},
+};
+
+/// One match of a backtest dataset: the statistics known before kick-off,
+/// the odds per market code and the final score.
+///
+/// Statistics are keyed by the parts of the rule that reads them, joined
+/// with dots in the order type, target, time, trend and probability, e.g.
+/// `HOME.GOALS_SCORED.LAST_5.AVERAGE`. Parts a rule leaves unset are skipped.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct MatchRecord {
+    #[serde(default)]
+    pub date: String,
+
+    #[serde(default)]
+    pub home_team: String,
+
+    #[serde(default)]
+    pub away_team: String,
+
+    pub home_goals: u32,
+    pub away_goals: u32,
+
+    #[serde(default)]
+    pub ht_home_goals: Option<u32>,
+
+    #[serde(default)]
+    pub ht_away_goals: Option<u32>,
+
+    #[serde(default)]
+    pub home_corners: Option<u32>,
+
+    #[serde(default)]
+    pub away_corners: Option<u32>,
+
+    /// Decimal odds by market code, e.g. `1`, `X2` or `O25`
+    #[serde(default)]
+    pub odds: HashMap<String, f32>,
+
+    #[serde(default)]
+    pub stats: HashMap<String, f32>,
+}
+
+fn stat_key(rule: &Rule, rule_type: Option<&str>) -> String {
+    [
+        rule_type,
+        rule.target.as_ref().map(|t| t.as_str()),
+        rule.time.as_ref().map(|t| t.as_str()),
+        rule.trend_stats.as_ref().map(|t| t.as_str()),
+        rule.probability.as_ref().map(|p| p.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(".")
+}
+
+fn settle_line(value: u32, side: Side, line: Line) -> PickResult {
+    let value = value * 10;
+    let line = line.0 as u32;
+
+    if value == line {
+        PickResult::Void
+    } else if (value > line) == (side == Side::Over) {
+        PickResult::Won
+    } else {
+        PickResult::Lost
+    }
+}
+
+fn settle_bool(won: bool) -> PickResult {
+    if won {
+        PickResult::Won
+    } else {
+        PickResult::Lost
+    }
+}
+
+impl MatchRecord {
+    /// The team with the shorter `1`/`2` odds, if both are known and differ.
+    pub fn favorite(&self) -> Option<Team> {
+        let home = self.odds.get("1")?;
+        let away = self.odds.get("2")?;
+
+        match home.partial_cmp(away)? {
+            std::cmp::Ordering::Less => Some(Team::Home),
+            std::cmp::Ordering::Greater => Some(Team::Away),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// `Some` when the favourite is `favorite`, which the "at home" markets
+    /// need to apply at all.
+    fn at_home(&self, favorite: Team) -> Option<()> {
+        (self.favorite()? == favorite).then_some(())
+    }
+
+    /// Whether a market can be bet on this match. The "at home" markets only
+    /// exist when the favourite, or the underdog, plays at home.
+    pub fn offers(&self, market: &Market) -> bool {
+        match market {
+            Market::FavoriteWinsAtHome => self.favorite() != Some(Team::Away),
+            Market::UnderdogWinsAtHome => self.favorite() != Some(Team::Home),
+            _ => true,
+        }
+    }
+
+    /// The team a rule type refers to: `Some(None)` for totals, and `None`
+    /// when the favourite can't be told from the odds.
+    fn side_of(&self, rule_type: Option<&RuleType>) -> Option<Option<Team>> {
+        match rule_type {
+            Some(RuleType::Home) => Some(Some(Team::Home)),
+            Some(RuleType::Away) => Some(Some(Team::Away)),
+            Some(RuleType::Favorite) => self.favorite().map(Some),
+            Some(RuleType::Underdog) => self.favorite().map(|team| Some(other(team))),
+            _ => Some(None),
+        }
+    }
+
+    /// The value a rule compares against its threshold. Favourite and
+    /// underdog statistics fall back to the home or away ones, and odds
+    /// rules to the `1`/`2` odds, when the dataset doesn't list them.
+    pub fn stat(&self, rule: &Rule) -> Option<f32> {
+        let rule_type = rule.rule_type.as_ref();
+
+        if let Some(value) = self
+            .stats
+            .get(&stat_key(rule, rule_type.map(|t| t.as_str())))
+        {
+            return Some(*value);
+        }
+
+        let team = self.side_of(rule_type)?;
+
+        if let Some(team) = team {
+            let resolved = match team {
+                Team::Home => RuleType::Home,
+                Team::Away => RuleType::Away,
+            };
+
+            if let Some(value) = self.stats.get(&stat_key(rule, Some(resolved.as_str()))) {
+                return Some(*value);
+            }
+        }
+
+        match (rule.target.as_ref(), team) {
+            (Some(Target::Odds), Some(Team::Home)) => self.odds.get("1").copied(),
+            (Some(Target::Odds), Some(Team::Away)) => self.odds.get("2").copied(),
+            _ => None,
+        }
+    }
+
+    /// The odds a market was priced at. Favourite and underdog markets fall
+    /// back to the `1`/`2` odds of the team they back.
+    pub fn odds_for(&self, market: &Market) -> Option<f32> {
+        if let Some(odds) = self.odds.get(&market.to_string()) {
+            return Some(*odds);
+        }
+
+        let team = match market {
+            Market::FavoriteWins => self.favorite()?,
+            Market::UnderdogWins => other(self.favorite()?),
+            // The team that has to win plays at home
+            Market::FavoriteWinsAtHome => {
+                self.at_home(Team::Home)?;
+                Team::Home
+            }
+            Market::UnderdogWinsAtHome => {
+                self.at_home(Team::Away)?;
+                Team::Home
+            }
+            _ => return None,
+        };
+
+        match team {
+            Team::Home => self.odds.get("1").copied(),
+            Team::Away => self.odds.get("2").copied(),
+        }
+    }
+
+    fn goals(&self, team: Team) -> u32 {
+        match team {
+            Team::Home => self.home_goals,
+            Team::Away => self.away_goals,
+        }
+    }
+
+    fn half_time(&self) -> Option<(u32, u32)> {
+        Some((self.ht_home_goals?, self.ht_away_goals?))
+    }
+
+    /// Settles a market on the final score, or `None` when the dataset lacks
+    /// the half-time score, corners or odds the market depends on.
+    pub fn settle(&self, market: &Market) -> Option<PickResult> {
+        let (home, away) = (self.home_goals, self.away_goals);
+
+        let result = match market {
+            Market::HomeWin => settle_bool(home > away),
+            Market::Draw => settle_bool(home == away),
+            Market::AwayWin => settle_bool(home < away),
+            Market::HomeOrAway => settle_bool(home != away),
+            Market::HomeOrDraw => settle_bool(home >= away),
+            Market::DrawOrAway => settle_bool(home <= away),
+            Market::FavoriteWins => {
+                let favorite = self.favorite()?;
+                settle_bool(self.goals(favorite) > self.goals(other(favorite)))
+            }
+            Market::FavoriteWinsAtHome => {
+                self.at_home(Team::Home)?;
+                settle_bool(home > away)
+            }
+            Market::UnderdogWins => {
+                let underdog = other(self.favorite()?);
+                settle_bool(self.goals(underdog) > self.goals(other(underdog)))
+            }
+            Market::UnderdogWinsAtHome => {
+                self.at_home(Team::Away)?;
+                settle_bool(home > away)
+            }
+            Market::BothTeamsScore => settle_bool(home > 0 && away > 0),
+            Market::NoBothTeamsScore => settle_bool(home == 0 || away == 0),
+            Market::Goals { side, line } => settle_line(home + away, *side, *line),
+            Market::TeamGoals { team, side, line } => settle_line(self.goals(*team), *side, *line),
+            Market::HalfTimeHomeWin => {
+                let (home, away) = self.half_time()?;
+                settle_bool(home > away)
+            }
+            Market::HalfTimeDraw => {
+                let (home, away) = self.half_time()?;
+                settle_bool(home == away)
+            }
+            Market::HalfTimeAwayWin => {
+                let (home, away) = self.half_time()?;
+                settle_bool(home < away)
+            }
+            Market::HalfTimeBothTeamsScore => {
+                let (home, away) = self.half_time()?;
+                settle_bool(home > 0 && away > 0)
+            }
+            Market::HalfTimeGoals { side, line } => {
+                let (home, away) = self.half_time()?;
+                settle_line(home + away, *side, *line)
+            }
+            Market::Corners { side, line } => {
+                settle_line(self.home_corners? + self.away_corners?, *side, *line)
+            }
+            // Depends on the score when the pick was made, which isn't known
+            Market::GoalsSincePicked { .. } | Market::Unknown(_) => return None,
+        };
+
+        Some(result)
+    }
+}
+
+fn other(team: Team) -> Team {
+    match team {
+        Team::Home => Team::Away,
+        Team::Away => Team::Home,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(home_goals: u32, away_goals: u32, home_odds: f32, away_odds: f32) -> MatchRecord {
+        MatchRecord {
+            home_goals,
+            away_goals,
+            odds: HashMap::from([("1".to_string(), home_odds), ("2".to_string(), away_odds)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lines_void_when_the_total_lands_on_them() {
+        assert_eq!(settle_line(2, Side::Over, Line(20)), PickResult::Void);
+        assert_eq!(settle_line(2, Side::Under, Line(20)), PickResult::Void);
+        assert_eq!(settle_line(0, Side::Under, Line(0)), PickResult::Void);
+
+        assert_eq!(settle_line(3, Side::Over, Line(25)), PickResult::Won);
+        assert_eq!(settle_line(2, Side::Over, Line(25)), PickResult::Lost);
+        assert_eq!(settle_line(2, Side::Under, Line(25)), PickResult::Won);
+        assert_eq!(settle_line(3, Side::Under, Line(25)), PickResult::Lost);
+        assert_eq!(settle_line(3, Side::Over, Line(20)), PickResult::Won);
+    }
+
+    #[test]
+    fn goal_markets_settle_on_the_final_score() {
+        let data = record(2, 1, 1.8, 4.0);
+
+        assert_eq!(data.settle(&Market::parse("O25")), Some(PickResult::Won));
+        assert_eq!(data.settle(&Market::parse("U35")), Some(PickResult::Won));
+        assert_eq!(
+            data.settle(&Market::parse("Home_O15")),
+            Some(PickResult::Won)
+        );
+        assert_eq!(
+            data.settle(&Market::TeamGoals {
+                team: Team::Away,
+                side: Side::Under,
+                line: Line(5),
+            }),
+            Some(PickResult::Lost)
+        );
+        assert_eq!(data.settle(&Market::parse("+0.5HT")), None);
+        assert_eq!(data.settle(&Market::parse("CO95")), None);
+    }
+
+    #[test]
+    fn favourite_and_underdog_follow_the_odds() {
+        let home_favourite = record(2, 1, 1.8, 4.0);
+        let away_favourite = record(2, 1, 4.0, 1.8);
+
+        assert_eq!(home_favourite.favorite(), Some(Team::Home));
+        assert_eq!(away_favourite.favorite(), Some(Team::Away));
+
+        let settle = |data: &MatchRecord, market| data.settle(&market);
+
+        assert_eq!(
+            settle(&home_favourite, Market::FavoriteWins),
+            Some(PickResult::Won)
+        );
+        assert_eq!(
+            settle(&home_favourite, Market::FavoriteWinsAtHome),
+            Some(PickResult::Won)
+        );
+        assert_eq!(
+            settle(&home_favourite, Market::UnderdogWins),
+            Some(PickResult::Lost)
+        );
+        assert_eq!(settle(&home_favourite, Market::UnderdogWinsAtHome), None);
+        assert!(!home_favourite.offers(&Market::UnderdogWinsAtHome));
+
+        assert_eq!(
+            settle(&away_favourite, Market::FavoriteWins),
+            Some(PickResult::Lost)
+        );
+        assert_eq!(settle(&away_favourite, Market::FavoriteWinsAtHome), None);
+        assert!(!away_favourite.offers(&Market::FavoriteWinsAtHome));
+        assert_eq!(
+            settle(&away_favourite, Market::UnderdogWins),
+            Some(PickResult::Won)
+        );
+        assert_eq!(
+            settle(&away_favourite, Market::UnderdogWinsAtHome),
+            Some(PickResult::Won)
+        );
+
+        assert_eq!(away_favourite.odds_for(&Market::FavoriteWins), Some(1.8));
+        assert_eq!(away_favourite.odds_for(&Market::UnderdogWins), Some(4.0));
+        assert_eq!(
+            away_favourite.odds_for(&Market::UnderdogWinsAtHome),
+            Some(4.0)
+        );
+        assert_eq!(away_favourite.odds_for(&Market::FavoriteWinsAtHome), None);
+        assert_eq!(
+            home_favourite.odds_for(&Market::FavoriteWinsAtHome),
+            Some(1.8)
+        );
+        assert_eq!(home_favourite.odds_for(&Market::UnderdogWinsAtHome), None);
+    }
+
+    #[test]
+    fn favourite_markets_need_distinct_odds() {
+        let even = record(1, 0, 2.5, 2.5);
+
+        assert_eq!(even.favorite(), None);
+        assert_eq!(even.settle(&Market::FavoriteWins), None);
+        assert_eq!(even.odds_for(&Market::UnderdogWins), None);
+        assert_eq!(MatchRecord::default().settle(&Market::FavoriteWins), None);
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::market::Market;
+
+/// How a pick was settled. Anything the API reports that isn't a win, loss
+/// or void is treated as still pending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum PickResult {
+    Won,
+    Lost,
+    Void,
+    Pending,
+}
+
+impl PickResult {
+    pub fn parse(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "won" | "win" | "w" => PickResult::Won,
+            "lost" | "lose" | "loss" | "l" => PickResult::Lost,
+            "void" | "refund" | "refunded" | "cancelled" => PickResult::Void,
+            _ => PickResult::Pending,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PickResult::Won => "won",
+            PickResult::Lost => "lost",
+            PickResult::Void => "void",
+            PickResult::Pending => "pending",
+        }
+    }
+
+    /// Whether the pick has a win or loss to count.
+    pub fn is_settled(&self) -> bool {
+        matches!(self, PickResult::Won | PickResult::Lost)
+    }
+}
+
+impl fmt::Display for PickResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for PickResult {
+    fn from(value: String) -> Self {
+        PickResult::parse(&value)
+    }
+}
+
+impl From<PickResult> for String {
+    fn from(result: PickResult) -> Self {
+        result.as_str().to_string()
+    }
+}
+
+/// One match a filter picked, as listed on its history page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pick {
+    #[serde(default, rename = "matchId", alias = "fixtureId")]
+    pub match_id: Option<i64>,
+
+    #[serde(default, alias = "matchDate", alias = "kickoff")]
+    pub date: String,
+
+    #[serde(default, rename = "homeTeam")]
+    pub home_team: String,
+
+    #[serde(default, rename = "awayTeam")]
+    pub away_team: String,
+
+    #[serde(default, alias = "desiredOutcome")]
+    pub outcome: Option<Market>,
+
+    #[serde(default)]
+    pub odds: Option<f32>,
+
+    #[serde(default = "pending", alias = "status")]
+    pub result: PickResult,
+}
+
+fn pending() -> PickResult {
+    PickResult::Pending
+}
+
+impl Pick {
+    /// Identifies the match across filters, falling back to the date and
+    /// teams when the API doesn't include a match ID.
+    pub fn match_key(&self) -> String {
+        match self.match_id {
+            Some(id) => id.to_string(),
+            None => format!("{}|{}|{}", self.date, self.home_team, self.away_team),
+        }
+    }
+
+    /// Profit of a one-unit stake, or `None` unless the pick was won or lost
+    /// at known odds. Losses without odds are left out like wins without
+    /// them, so that missing odds don't skew the profit either way.
+    pub fn profit(&self) -> Option<f64> {
+        let odds = self.odds? as f64;
+
+        match self.result {
+            PickResult::Won => Some(odds - 1.0),
+            PickResult::Lost => Some(-1.0),
+            PickResult::Void | PickResult::Pending => None,
+        }
+    }
+}
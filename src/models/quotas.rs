@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::{
+    filter::Filter,
+    market::{Market, MarketCategory},
+};
+
+/// Caps on how many filters the portfolio may hold per market, so that
+/// selection does not concentrate on a single outcome.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Quotas {
+    /// Cap for every outcome without its own entry in `outcomes`
+    pub max_per_outcome: Option<u32>,
+
+    /// Caps per outcome, keyed by its spelling, e.g. `O25 = 2`
+    pub outcomes: BTreeMap<String, u32>,
+
+    /// Caps per market category: result, goals, corners, half-time or other
+    pub categories: BTreeMap<String, u32>,
+}
+
+impl Quotas {
+    pub fn is_empty(&self) -> bool {
+        self.max_per_outcome.is_none() && self.outcomes.is_empty() && self.categories.is_empty()
+    }
+
+    /// Checks that every key names a catalog outcome or a category, since a
+    /// misspelled key would otherwise never cap anything.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(outcome) = self
+            .outcomes
+            .keys()
+            .find(|code| matches!(Market::parse(code), Market::Unknown(_)))
+        {
+            return Err(format!("unknown outcome '{}' in quotas.outcomes", outcome));
+        }
+
+        let categories: Vec<String> = MarketCategory::ALL.iter().map(|c| c.to_string()).collect();
+
+        if let Some(category) = self
+            .categories
+            .keys()
+            .find(|name| !categories.contains(name))
+        {
+            return Err(format!(
+                "unknown category '{}' in quotas.categories, expected one of {}",
+                category,
+                categories.join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts filters per outcome and category, starting from the filters
+/// already held, and checks candidates against the quotas.
+pub struct QuotaTracker<'a> {
+    quotas: &'a Quotas,
+    outcomes: HashMap<String, u32>,
+    categories: HashMap<String, u32>,
+}
+
+impl<'a> QuotaTracker<'a> {
+    pub fn new(quotas: &'a Quotas, holdings: &[Filter]) -> Self {
+        let mut tracker = Self {
+            quotas,
+            outcomes: HashMap::new(),
+            categories: HashMap::new(),
+        };
+
+        for filter in holdings {
+            tracker.add(filter);
+        }
+
+        tracker
+    }
+
+    pub fn add(&mut self, filter: &Filter) {
+        if let Some(outcome) = &filter.desired_outcome {
+            *self.outcomes.entry(outcome.to_string()).or_default() += 1;
+            *self
+                .categories
+                .entry(outcome.category().to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns why the filter would exceed a quota, or `None` if it fits.
+    pub fn check(&self, filter: &Filter) -> Option<String> {
+        let outcome = filter.desired_outcome.as_ref()?;
+        let outcome_key = outcome.to_string();
+        let category_key = outcome.category().to_string();
+
+        let outcome_limit = self
+            .quotas
+            .outcomes
+            .get(&outcome_key)
+            .copied()
+            .or(self.quotas.max_per_outcome);
+        let held = self.outcomes.get(&outcome_key).copied().unwrap_or_default();
+
+        if let Some(limit) = outcome_limit.filter(|limit| held >= *limit) {
+            return Some(format!(
+                "outcome {} is at its cap of {}",
+                outcome_key, limit
+            ));
+        }
+
+        let held = self
+            .categories
+            .get(&category_key)
+            .copied()
+            .unwrap_or_default();
+
+        match self.quotas.categories.get(&category_key) {
+            Some(limit) if held >= *limit => Some(format!(
+                "category {} is at its cap of {}",
+                category_key, limit
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotas(outcomes: &[&str], categories: &[&str]) -> Quotas {
+        Quotas {
+            max_per_outcome: None,
+            outcomes: outcomes.iter().map(|o| (o.to_string(), 1)).collect(),
+            categories: categories.iter().map(|c| (c.to_string(), 1)).collect(),
+        }
+    }
+
+    #[test]
+    fn catalog_outcomes_and_categories_are_valid() {
+        let valid = quotas(
+            &["O25", "+0.5", "1X", "CO95", "Favorite wins", "-1.5HT"],
+            &["result", "goals", "corners", "half-time", "other"],
+        );
+
+        assert_eq!(valid.validate(), Ok(()));
+    }
+
+    #[test]
+    fn misspelled_keys_are_rejected() {
+        assert_eq!(
+            quotas(&["O2.5"], &[]).validate(),
+            Err("unknown outcome 'O2.5' in quotas.outcomes".to_string())
+        );
+        assert!(quotas(&[], &["goal"])
+            .validate()
+            .unwrap_err()
+            .starts_with("unknown category 'goal' in quotas.categories, expected one of result"));
+    }
+}
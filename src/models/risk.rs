@@ -0,0 +1,162 @@
+use serde::Serialize;
+use std::fmt;
+
+use super::pick::{Pick, PickResult};
+
+/// How painful a filter is to follow at a flat one-unit stake, derived from
+/// its picks in match order that were won or lost at known odds.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RiskMetrics {
+    /// Settled picks with odds the metrics are based on
+    pub picks: u32,
+    /// Largest fall in cumulative profit from a previous high, in units
+    pub max_drawdown: f64,
+    /// Most consecutive losses, ignoring voids in between
+    pub losing_streak: u32,
+    /// Standard deviation of the profit per pick, in units
+    pub volatility: f64,
+    /// Most picks it took to get back to a previous high, counting a
+    /// drawdown that hasn't recovered yet
+    pub time_to_recover: u32,
+}
+
+impl RiskMetrics {
+    /// `None` when none of the picks are settled at known odds. Voids,
+    /// pending picks and picks without odds are skipped.
+    pub fn from_picks(picks: &[Pick]) -> Option<Self> {
+        let settled: Vec<(PickResult, f64)> = picks
+            .iter()
+            .filter_map(|pick| pick.profit().map(|profit| (pick.result, profit)))
+            .collect();
+
+        if settled.is_empty() {
+            return None;
+        }
+
+        let mut equity = 0.0;
+        let mut peak = 0.0;
+        let mut max_drawdown: f64 = 0.0;
+        let mut underwater = 0;
+        let mut time_to_recover = 0;
+        let mut streak = 0;
+        let mut losing_streak = 0;
+
+        for (result, profit) in &settled {
+            equity += profit;
+
+            if equity >= peak {
+                peak = equity;
+                underwater = 0;
+            } else {
+                underwater += 1;
+                max_drawdown = max_drawdown.max(peak - equity);
+                time_to_recover = time_to_recover.max(underwater);
+            }
+
+            match result {
+                PickResult::Lost => {
+                    streak += 1;
+                    losing_streak = losing_streak.max(streak);
+                }
+                _ => streak = 0,
+            }
+        }
+
+        let n = settled.len() as f64;
+        let mean = equity / n;
+        let variance = settled
+            .iter()
+            .map(|(_, profit)| (profit - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        Some(Self {
+            picks: settled.len() as u32,
+            max_drawdown,
+            losing_streak,
+            volatility: variance.sqrt(),
+            time_to_recover,
+        })
+    }
+}
+
+impl fmt::Display for RiskMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "max drawdown {:.2}u, longest losing streak {}, volatility {:.2}u, time to recover {} picks (over {} priced picks)",
+            self.max_drawdown, self.losing_streak, self.volatility, self.time_to_recover, self.picks
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(result: PickResult, odds: Option<f32>) -> Pick {
+        Pick {
+            match_id: None,
+            date: String::new(),
+            home_team: String::new(),
+            away_team: String::new(),
+            outcome: None,
+            odds,
+            result,
+        }
+    }
+
+    fn won(odds: f32) -> Pick {
+        pick(PickResult::Won, Some(odds))
+    }
+
+    fn lost() -> Pick {
+        pick(PickResult::Lost, Some(2.0))
+    }
+
+    #[test]
+    fn drawdown_and_streak_follow_the_equity_curve() {
+        // Equity: +1, 0, -1, -2, -0.5, +2.5, +1.5
+        let picks = [won(2.0), lost(), lost(), lost(), won(2.5), won(4.0), lost()];
+        let risk = RiskMetrics::from_picks(&picks).unwrap();
+
+        assert_eq!(risk.picks, 7);
+        assert_eq!(risk.max_drawdown, 3.0);
+        assert_eq!(risk.losing_streak, 3);
+        assert_eq!(risk.time_to_recover, 4);
+        assert!(
+            (risk.volatility - 1.5085).abs() < 1e-3,
+            "{}",
+            risk.volatility
+        );
+    }
+
+    #[test]
+    fn voids_pending_and_unpriced_picks_are_skipped() {
+        let picks = [
+            lost(),
+            pick(PickResult::Void, Some(1.9)),
+            pick(PickResult::Won, None),
+            pick(PickResult::Pending, Some(1.8)),
+            pick(PickResult::Lost, None),
+            lost(),
+        ];
+        let risk = RiskMetrics::from_picks(&picks).unwrap();
+
+        assert_eq!(risk.picks, 2);
+        assert_eq!(risk.max_drawdown, 2.0);
+        assert_eq!(risk.losing_streak, 2);
+        assert_eq!(risk.volatility, 0.0);
+    }
+
+    #[test]
+    fn no_priced_picks_means_no_metrics() {
+        let picks = [
+            pick(PickResult::Void, Some(1.9)),
+            pick(PickResult::Won, None),
+            pick(PickResult::Pending, Some(1.8)),
+        ];
+
+        assert!(RiskMetrics::from_picks(&picks).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// Declares an enum over the string values betmines uses for a rule field.
+///
+/// Unrecognised values are kept in `Unknown` so serde round-trips stay
+/// lossless. Equality, hashing and ordering go through the string value,
+/// which keeps them identical to comparing the raw strings.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)+
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => Self::$variant,)+
+                    _ => Self::Unknown(value),
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self::from(value.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.as_str().to_string()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state);
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// How a statistic is compared against `valueToCompare`
+    ComparatorType {
+        GreaterThan => "GREATER_THAN",
+        GreaterOrEqual => "GREATER_OR_EQUAL",
+        LessThan => "LESS_THAN",
+        LessOrEqual => "LESS_OR_EQUAL",
+        Equal => "EQUAL",
+        NotEqual => "NOT_EQUAL",
+    }
+}
+
+impl ComparatorType {
+    /// Applies the comparator, returning `None` when its meaning is unknown.
+    pub fn compare(&self, value: f32, threshold: f32) -> Option<bool> {
+        match self {
+            ComparatorType::GreaterThan => Some(value > threshold),
+            ComparatorType::GreaterOrEqual => Some(value >= threshold),
+            ComparatorType::LessThan => Some(value < threshold),
+            ComparatorType::LessOrEqual => Some(value <= threshold),
+            ComparatorType::Equal => Some(value == threshold),
+            ComparatorType::NotEqual => Some(value != threshold),
+            ComparatorType::Unknown(_) => None,
+        }
+    }
+}
+
+string_enum! {
+    /// The statistic a rule looks at
+    Target {
+        Goals => "GOALS",
+        GoalsScored => "GOALS_SCORED",
+        GoalsConceded => "GOALS_CONCEDED",
+        Corners => "CORNERS",
+        Shots => "SHOTS",
+        ShotsOnGoal => "SHOTS_ON_TARGET",
+        Possession => "POSSESSION",
+        Attacks => "ATTACKS",
+        DangerousAttacks => "DANGEROUS_ATTACKS",
+        Cards => "CARDS",
+        ExpectedGoals => "XG",
+        Odds => "ODDS",
+        Points => "POINTS",
+        Position => "POSITION",
+    }
+}
+
+string_enum! {
+    /// Which side of the match a rule applies to
+    RuleType {
+        Home => "HOME",
+        Away => "AWAY",
+        Total => "TOTAL",
+        Favorite => "FAVORITE",
+        Underdog => "UNDERDOG",
+    }
+}
+
+string_enum! {
+    /// The period a statistic is measured over
+    TimeWindow {
+        FullTime => "FT",
+        FirstHalf => "HT",
+        SecondHalf => "2HT",
+        Last5 => "LAST_5",
+        Last10 => "LAST_10",
+        Season => "SEASON",
+        Live => "LIVE",
+    }
+}
+
+string_enum! {
+    /// How the statistic is aggregated over the time window
+    TrendStats {
+        Average => "AVERAGE",
+        Total => "TOTAL",
+        Percentage => "PERCENTAGE",
+        Min => "MIN",
+        Max => "MAX",
+    }
+}
+
+string_enum! {
+    /// Confidence bucket betmines attaches to probability based rules
+    Probability {
+        Low => "LOW",
+        Medium => "MEDIUM",
+        High => "HIGH",
+        VeryHigh => "VERY_HIGH",
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How the raw pick count is scaled before its weight is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PicksNormalisation {
+    Linear,
+    Log,
+    Sqrt,
+    /// Counts picks up to the given cap, ignoring any beyond it
+    Cap(u32),
+}
+
+impl PicksNormalisation {
+    pub fn apply(&self, picks: u32) -> f64 {
+        match self {
+            PicksNormalisation::Linear => picks as f64,
+            PicksNormalisation::Log => (picks as f64).ln_1p(),
+            PicksNormalisation::Sqrt => (picks as f64).sqrt(),
+            PicksNormalisation::Cap(cap) => picks.min(*cap) as f64,
+        }
+    }
+}
+
+impl TryFrom<String> for PicksNormalisation {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "linear" => Ok(PicksNormalisation::Linear),
+            "log" => Ok(PicksNormalisation::Log),
+            "sqrt" => Ok(PicksNormalisation::Sqrt),
+            _ => value
+                .strip_prefix("cap:")
+                .and_then(|cap| cap.parse().ok())
+                .map(PicksNormalisation::Cap)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid picks normalisation '{}', expected linear, log, sqrt or cap:<n>",
+                        value
+                    )
+                }),
+        }
+    }
+}
+
+impl From<PicksNormalisation> for String {
+    fn from(value: PicksNormalisation) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for PicksNormalisation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PicksNormalisation::Linear => f.write_str("linear"),
+            PicksNormalisation::Log => f.write_str("log"),
+            PicksNormalisation::Sqrt => f.write_str("sqrt"),
+            PicksNormalisation::Cap(cap) => write!(f, "cap:{}", cap),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoringMethod {
+    /// Weighs the ROI and success rate as reported
+    Weighted,
+    /// Weighs the lower confidence bounds of ROI and success rate, so small
+    /// samples are penalised
+    Wilson,
+}
+
+/// Wilson score interval for a success rate given in percent.
+pub fn wilson_interval(success_rate: f64, picks: u32, z: f64) -> (f64, f64) {
+    if picks == 0 {
+        return (0.0, 100.0);
+    }
+
+    let n = picks as f64;
+    let p = (success_rate / 100.0).clamp(0.0, 1.0);
+    let z2 = z * z;
+
+    let centre = p + z2 / (2.0 * n);
+    let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    let denominator = 1.0 + z2 / n;
+
+    (
+        100.0 * (centre - margin) / denominator,
+        100.0 * (centre + margin) / denominator,
+    )
+}
+
+/// Normal-approximation interval for an ROI given in percent.
+///
+/// Assumes flat stakes at the average odds implied by ROI and success rate,
+/// so each pick returns `odds - 1` or `-1`.
+pub fn roi_interval(roi: f64, success_rate: f64, picks: u32, z: f64) -> (f64, f64) {
+    let p = (success_rate / 100.0).clamp(0.0, 1.0);
+
+    if picks == 0 || p == 0.0 {
+        return (roi, roi);
+    }
+
+    let odds = (1.0 + roi / 100.0) / p;
+    let standard_error = odds * (p * (1.0 - p) / picks as f64).sqrt();
+    let margin = 100.0 * z * standard_error;
+
+    (roi - margin, roi + margin)
+}
+
+/// Weights used to turn a filter's metrics into a single ranking score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringProfile {
+    pub method: ScoringMethod,
+
+    /// Standard score of the confidence intervals, 1.96 for 95%
+    pub z: f64,
+
+    pub roi_weight: f64,
+    pub success_rate_weight: f64,
+    pub picks_weight: f64,
+
+    /// Filters with an ROI below this score 0
+    pub min_roi: f64,
+
+    pub picks: PicksNormalisation,
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        Self {
+            method: ScoringMethod::Weighted,
+            z: 1.96,
+            roi_weight: 0.8,
+            success_rate_weight: 0.3,
+            picks_weight: 0.1,
+            min_roi: 0.0,
+            picks: PicksNormalisation::Linear,
+        }
+    }
+}
+
+impl ScoringProfile {
+    /// Default weights applied to the pessimistic bounds instead. Picks carry
+    /// no weight of their own since the sample size already shapes the bounds.
+    pub fn wilson() -> Self {
+        Self {
+            method: ScoringMethod::Wilson,
+            picks_weight: 0.0,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoreBreakdown {
+    pub roi: f64,
+    pub success_rate: f64,
+    pub picks: f64,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> f64 {
+        self.roi + self.success_rate + self.picks
+    }
+}
+
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} (ROI {:.2} + Success Rate {:.2} + Picks {:.2})",
+            self.total(),
+            self.roi,
+            self.success_rate,
+            self.picks
+        )
+    }
+}
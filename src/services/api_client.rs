@@ -0,0 +1,307 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{
+    errors::CliError,
+    models::{filter::Filter, pick::Pick},
+    utils::{config, credentials},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Debug)]
+struct LoginRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LoginResponse {
+    #[serde(alias = "accessToken", alias = "access_token")]
+    pub token: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct UserProfile {
+    #[serde(default)]
+    pub id: Option<i64>,
+
+    #[serde(default)]
+    pub username: String,
+
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+pub struct ApiClient {
+    agent: ureq::Agent,
+    base_url: String,
+    token: Option<String>,
+    /// Whether the token is the stored session, which is flagged as expired
+    /// when the API rejects it
+    stored_token: bool,
+}
+
+impl ApiClient {
+    /// Creates an authenticated client for the live or pre-match filters API.
+    pub fn new(live: bool) -> Self {
+        Self::with_base_url(config::get_api_base_url(live)).with_stored_token()
+    }
+
+    /// Creates an authenticated client for account-level endpoints.
+    pub fn account() -> Self {
+        Self::with_base_url(config::get_api_root()).with_stored_token()
+    }
+
+    /// Creates a client against an arbitrary base URL, e.g. a local mock server.
+    pub fn with_base_url(base_url: &str) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+            stored_token: false,
+        }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    fn with_stored_token(self) -> Self {
+        match credentials::load() {
+            Ok(Some(stored)) => Self {
+                stored_token: true,
+                ..self.with_token(stored.token)
+            },
+            Ok(None) => self,
+            Err(err) => {
+                log::warn!("Failed to read stored credentials: {}", err);
+                self
+            }
+        }
+    }
+
+    /// Exchanges a username and password for a session token.
+    pub fn login(&self, username: &str, password: &str) -> Result<LoginResponse, CliError> {
+        let url = config::login_endpoint(&self.base_url);
+        let request = LoginRequest { username, password };
+
+        match self.agent.post(&url).send_json(&request) {
+            Ok(response) => parse_response(response),
+            Err(ureq::Error::Status(401 | 403, _)) => {
+                Err(CliError::AuthError("Invalid username or password".into()))
+            }
+            Err(err) => Err(map_error(err, false)),
+        }
+    }
+
+    /// Fetches the profile of the account the session token belongs to.
+    pub fn current_user(&self) -> Result<UserProfile, CliError> {
+        let url = config::profile_endpoint(&self.base_url);
+        self.send_json(self.agent.get(&url))
+    }
+
+    /// Fetches the filters saved on the account.
+    pub fn fetch_filters(&self) -> Result<Vec<Filter>, CliError> {
+        self.send_json(self.agent.get(&self.base_url))
+    }
+
+    /// Fetches the filters listed by the public scanner.
+    pub fn fetch_scanner_filters(&self) -> Result<Vec<Filter>, CliError> {
+        let url = config::scanner_endpoint(&self.base_url);
+        self.send_json(self.agent.get(&url))
+    }
+
+    /// Fetches the matches a filter picked, as shown on its history page.
+    pub fn fetch_picks(&self, filter_id: i32) -> Result<Vec<Pick>, CliError> {
+        let url = config::history_endpoint(&self.base_url, filter_id);
+        self.send_json(self.agent.get(&url))
+    }
+
+    /// Copies a filter into the account and returns the newly created filter.
+    pub fn copy_filter(&self, filter_id: i32) -> Result<Filter, CliError> {
+        let url = format!("{}/{}", config::copy_endpoint(&self.base_url), filter_id);
+        self.send_json(self.agent.post(&url))
+    }
+
+    pub fn delete_filter(&self, filter_id: i32) -> Result<(), CliError> {
+        let url = format!("{}{}", config::delete_endpoint(&self.base_url), filter_id);
+        self.send(self.agent.delete(&url)).map(|_| ())
+    }
+
+    /// Renames a filter by sending it back with an updated `serviceName`.
+    pub fn rename_filter(&self, filter: &Filter, new_name: &str) -> Result<Filter, CliError> {
+        let url = format!("{}/{}", self.base_url, filter.id);
+        let body = Filter {
+            name: new_name.to_string(),
+            ..filter.clone()
+        };
+
+        let response = self.send_with(self.agent.put(&url), &body)?;
+        parse_response(response)
+    }
+
+    fn authorize(&self, request: ureq::Request) -> ureq::Request {
+        match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    fn send(&self, request: ureq::Request) -> Result<ureq::Response, CliError> {
+        self.authorize(request)
+            .call()
+            .map_err(|err| self.handle_error(err))
+    }
+
+    fn send_with<B: Serialize>(
+        &self,
+        request: ureq::Request,
+        body: &B,
+    ) -> Result<ureq::Response, CliError> {
+        self.authorize(request)
+            .send_json(body)
+            .map_err(|err| self.handle_error(err))
+    }
+
+    fn send_json<T: DeserializeOwned>(&self, request: ureq::Request) -> Result<T, CliError> {
+        parse_response(self.send(request)?)
+    }
+
+    fn handle_error(&self, err: ureq::Error) -> CliError {
+        let err = map_error(err, self.token.is_some());
+
+        if self.stored_token && matches!(err, CliError::SessionExpired) {
+            if let Err(e) = credentials::mark_expired() {
+                log::warn!("Failed to update stored credentials: {}", e);
+            }
+        }
+
+        err
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: ureq::Response) -> Result<T, CliError> {
+    let body = response
+        .into_string()
+        .map_err(|e| CliError::NetworkError(format!("Failed to read response: {}", e)))?;
+
+    Ok(serde_json::from_str(&body)?)
+}
+
+fn map_error(err: ureq::Error, authenticated: bool) -> CliError {
+    match err {
+        ureq::Error::Status(401, _) if authenticated => CliError::SessionExpired,
+        ureq::Error::Status(401, _) => CliError::NotLoggedIn,
+        ureq::Error::Status(status, response) => CliError::ApiError {
+            status,
+            message: response.into_string().unwrap_or_default(),
+        },
+        ureq::Error::Transport(transport) => CliError::NetworkError(transport.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Serves a single canned response on a random local port and returns
+    /// its base URL.
+    fn serve_once(status: &str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let status = status.to_string();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn parses_successful_responses() {
+        let url = serve_once(
+            "200 OK",
+            r#"[{"id": 7, "roi": 12.5, "serviceName": "x", "rules": [], "totalPicks": 3, "successPercentage": 50, "desiredOutcome": "1"}]"#,
+        );
+
+        let filters = ApiClient::with_base_url(&url).fetch_filters().unwrap();
+
+        assert_eq!(filters.len(), 1);
+        assert_eq!(filters[0].id, 7);
+    }
+
+    #[test]
+    fn rejected_token_means_expired_session() {
+        let url = serve_once("401 Unauthorized", "");
+
+        let err = ApiClient::with_base_url(&url)
+            .with_token("stale")
+            .fetch_filters()
+            .unwrap_err();
+
+        assert!(matches!(err, CliError::SessionExpired), "{:?}", err);
+    }
+
+    #[test]
+    fn unauthorized_without_token_means_not_logged_in() {
+        let url = serve_once("401 Unauthorized", "");
+
+        let err = ApiClient::with_base_url(&url).fetch_filters().unwrap_err();
+
+        assert!(matches!(err, CliError::NotLoggedIn), "{:?}", err);
+    }
+
+    #[test]
+    fn other_statuses_become_api_errors() {
+        let url = serve_once("500 Internal Server Error", "boom");
+
+        let err = ApiClient::with_base_url(&url).delete_filter(7).unwrap_err();
+
+        match err {
+            CliError::ApiError { status, message } => {
+                assert_eq!(status, 500);
+                assert_eq!(message, "boom");
+            }
+            other => panic!("expected an API error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unreachable_servers_are_network_errors() {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let err = ApiClient::with_base_url(&format!("http://{}", address))
+            .fetch_filters()
+            .unwrap_err();
+
+        assert!(matches!(err, CliError::NetworkError(_)), "{:?}", err);
+    }
+}
@@ -0,0 +1,439 @@
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    errors::CliError,
+    models::{
+        filter::{Filter, Rule},
+        market::Market,
+        match_data::MatchRecord,
+        pick::PickResult,
+    },
+    services::report_service::ReportRow,
+    utils::filesystem,
+};
+
+/// Dataset columns with a fixed meaning. `odds.<code>` columns hold odds and
+/// every other column is a pre-match statistic.
+const SCORE_COLUMNS: &[&str] = &[
+    "home_goals",
+    "away_goals",
+    "ht_home_goals",
+    "ht_away_goals",
+    "home_corners",
+    "away_corners",
+];
+
+/// How a filter would have done on the dataset, next to what betmines
+/// reports for it.
+#[derive(Serialize, Debug, Clone)]
+pub struct BacktestRow {
+    pub id: i32,
+    pub name: String,
+    pub outcome: String,
+    /// Matches where every rule could be evaluated
+    pub evaluated: usize,
+    /// Matches missing a statistic some rule needs
+    pub skipped: usize,
+    pub picks: usize,
+    pub won: usize,
+    pub lost: usize,
+    pub void: usize,
+    /// Picks the dataset lacks the result to settle
+    pub unsettled: usize,
+    /// Won or lost picks without odds, left out of the ROI
+    pub unpriced: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_rate: Option<f64>,
+
+    /// Profit per unit staked on the picks won or lost at known odds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roi: Option<f64>,
+
+    pub reported_success_rate: f32,
+    pub reported_roi: f32,
+}
+
+fn format_percentage(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_default()
+}
+
+impl ReportRow for BacktestRow {
+    fn columns(_rows: &[Self]) -> Vec<&'static str> {
+        vec![
+            "id",
+            "name",
+            "outcome",
+            "evaluated",
+            "skipped",
+            "picks",
+            "won",
+            "lost",
+            "void",
+            "unsettled",
+            "unpriced",
+            "hit_rate",
+            "reported_success_rate",
+            "roi",
+            "reported_roi",
+        ]
+    }
+
+    fn cells(&self, _columns: &[&str]) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.outcome.clone(),
+            self.evaluated.to_string(),
+            self.skipped.to_string(),
+            self.picks.to_string(),
+            self.won.to_string(),
+            self.lost.to_string(),
+            self.void.to_string(),
+            self.unsettled.to_string(),
+            self.unpriced.to_string(),
+            format_percentage(self.hit_rate),
+            format!("{:.2}", self.reported_success_rate),
+            format_percentage(self.roi),
+            format!("{:.2}", self.reported_roi),
+        ]
+    }
+}
+
+fn dataset_error(path: &Path, message: impl std::fmt::Display) -> CliError {
+    CliError::DatasetError(format!("{}: {}", path.display(), message))
+}
+
+fn parse_cell<T: std::str::FromStr>(
+    path: &Path,
+    row: usize,
+    column: &str,
+    cell: &str,
+) -> Result<Option<T>, CliError> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+
+    cell.parse().map(Some).map_err(|_| {
+        dataset_error(
+            path,
+            format!(
+                "row {}, column '{}': invalid number '{}'",
+                row, column, cell
+            ),
+        )
+    })
+}
+
+fn load_csv(path: &Path) -> Result<Vec<MatchRecord>, CliError> {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| dataset_error(path, e))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| dataset_error(path, e))?
+        .clone();
+
+    for required in ["home_goals", "away_goals"] {
+        if !headers.iter().any(|header| header == required) {
+            return Err(dataset_error(
+                path,
+                format!("missing column '{}'", required),
+            ));
+        }
+    }
+
+    let mut matches = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| dataset_error(path, e))?;
+        let row = index + 2;
+        let mut data = MatchRecord::default();
+        let mut scores: HashMap<&str, u32> = HashMap::new();
+
+        for (column, cell) in headers.iter().zip(record.iter()) {
+            let cell = cell.trim();
+
+            match column {
+                "date" => data.date = cell.to_string(),
+                "home_team" => data.home_team = cell.to_string(),
+                "away_team" => data.away_team = cell.to_string(),
+                column if SCORE_COLUMNS.contains(&column) => {
+                    if let Some(value) = parse_cell(path, row, column, cell)? {
+                        scores.insert(column, value);
+                    }
+                }
+                column => {
+                    let Some(value) = parse_cell(path, row, column, cell)? else {
+                        continue;
+                    };
+
+                    match column.strip_prefix("odds.") {
+                        Some(code) => data.odds.insert(code.to_string(), value),
+                        None => data.stats.insert(column.to_string(), value),
+                    };
+                }
+            }
+        }
+
+        data.home_goals = *scores
+            .get("home_goals")
+            .ok_or_else(|| dataset_error(path, format!("row {}: missing home_goals", row)))?;
+        data.away_goals = *scores
+            .get("away_goals")
+            .ok_or_else(|| dataset_error(path, format!("row {}: missing away_goals", row)))?;
+        data.ht_home_goals = scores.get("ht_home_goals").copied();
+        data.ht_away_goals = scores.get("ht_away_goals").copied();
+        data.home_corners = scores.get("home_corners").copied();
+        data.away_corners = scores.get("away_corners").copied();
+
+        matches.push(data);
+    }
+
+    Ok(matches)
+}
+
+/// Loads a match dataset from a `.json` array of matches or a `.csv` file
+/// with one match per row.
+pub fn load_dataset(path: &Path) -> Result<Vec<MatchRecord>, CliError> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => load_csv(path),
+        _ => filesystem::load_data(path.to_string_lossy().to_string()),
+    }
+}
+
+enum RuleCheck {
+    Pass,
+    Fail,
+    Missing,
+}
+
+/// Why a rule can't be evaluated at all, regardless of the data.
+fn unsupported_reason(rule: &Rule) -> Option<String> {
+    match (&rule.comparator_type, rule.value_to_compare) {
+        (None, _) => Some("a rule has no comparator".into()),
+        (_, None) => Some("a rule has no value to compare".into()),
+        (Some(comparator), Some(value)) if comparator.compare(0.0, value).is_none() => {
+            Some(format!("unknown comparator '{}'", comparator))
+        }
+        _ => None,
+    }
+}
+
+fn check_rule(rule: &Rule, data: &MatchRecord) -> RuleCheck {
+    let (Some(comparator), Some(threshold)) = (&rule.comparator_type, rule.value_to_compare) else {
+        return RuleCheck::Missing;
+    };
+
+    match data
+        .stat(rule)
+        .and_then(|value| comparator.compare(value, threshold))
+    {
+        Some(true) => RuleCheck::Pass,
+        Some(false) => RuleCheck::Fail,
+        None => RuleCheck::Missing,
+    }
+}
+
+/// Why a filter can't be backtested, if it can't.
+pub fn unsupported(filter: &Filter) -> Option<String> {
+    match &filter.desired_outcome {
+        None => return Some("no desired outcome".into()),
+        Some(market @ (Market::GoalsSincePicked { .. } | Market::Unknown(_))) => {
+            return Some(format!(
+                "outcome '{}' can't be settled on a final score",
+                market
+            ))
+        }
+        Some(_) => {}
+    }
+
+    filter.rules.iter().find_map(unsupported_reason)
+}
+
+/// Runs a filter's rules over every match. A match is picked when all rules
+/// pass and its market is offered; when none fail but some statistic is
+/// missing it is skipped.
+pub fn backtest(filter: &Filter, matches: &[MatchRecord]) -> BacktestRow {
+    let market = filter.desired_outcome.as_ref();
+    let code = market.map(|m| m.to_string()).unwrap_or_default();
+
+    let mut row = BacktestRow {
+        id: filter.id,
+        name: filter.name.clone(),
+        outcome: code.clone(),
+        evaluated: 0,
+        skipped: 0,
+        picks: 0,
+        won: 0,
+        lost: 0,
+        void: 0,
+        unsettled: 0,
+        unpriced: 0,
+        hit_rate: None,
+        roi: None,
+        reported_success_rate: filter.success_rate,
+        reported_roi: filter.roi,
+    };
+
+    let mut profit = 0.0;
+    let mut priced = 0;
+
+    for data in matches {
+        let mut missing = false;
+        let mut failed = false;
+
+        for rule in &filter.rules {
+            match check_rule(rule, data) {
+                RuleCheck::Pass => {}
+                RuleCheck::Fail => {
+                    failed = true;
+                    break;
+                }
+                RuleCheck::Missing => missing = true,
+            }
+        }
+
+        if failed {
+            row.evaluated += 1;
+            continue;
+        }
+
+        if missing {
+            row.skipped += 1;
+            continue;
+        }
+
+        row.evaluated += 1;
+
+        // The rules passed, but the market isn't offered on this match
+        if market.is_some_and(|m| !data.offers(m)) {
+            continue;
+        }
+
+        row.picks += 1;
+
+        let Some(result) = market.and_then(|m| data.settle(m)) else {
+            row.unsettled += 1;
+            continue;
+        };
+
+        let odds = market.and_then(|m| data.odds_for(m));
+
+        match result {
+            PickResult::Won => row.won += 1,
+            PickResult::Lost => row.lost += 1,
+            PickResult::Void => row.void += 1,
+            PickResult::Pending => {}
+        }
+
+        // A loss without odds counts no more than a win without them, so
+        // missing odds can't bias the ROI either way
+        match (result, odds) {
+            (PickResult::Won, Some(odds)) => {
+                profit += odds as f64 - 1.0;
+                priced += 1;
+            }
+            (PickResult::Lost, Some(_)) => {
+                profit -= 1.0;
+                priced += 1;
+            }
+            (PickResult::Won | PickResult::Lost, None) => row.unpriced += 1,
+            (PickResult::Void | PickResult::Pending, _) => {}
+        }
+    }
+
+    let decided = row.won + row.lost;
+    if decided > 0 {
+        row.hit_rate = Some(row.won as f64 / decided as f64 * 100.0);
+    }
+    if priced > 0 {
+        row.roi = Some(profit / priced as f64 * 100.0);
+    }
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(home_goals: u32, away_goals: u32, odds: Option<f32>) -> MatchRecord {
+        MatchRecord {
+            home_goals,
+            away_goals,
+            odds: odds.into_iter().map(|o| ("1".to_string(), o)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn roi_only_counts_picks_with_odds() {
+        let filter: Filter = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "roi": 10.0,
+            "serviceName": "Home wins",
+            "rules": [],
+            "totalPicks": 10,
+            "successPercentage": 50.0,
+            "desiredOutcome": "1",
+        }))
+        .unwrap();
+
+        let matches = [
+            record(2, 0, Some(3.0)),
+            record(0, 1, Some(2.0)),
+            record(1, 0, None),
+            record(0, 0, None),
+            record(0, 2, None),
+        ];
+        let row = backtest(&filter, &matches);
+
+        assert_eq!((row.picks, row.won, row.lost), (5, 2, 3));
+        assert_eq!(row.unpriced, 3);
+        assert_eq!(row.hit_rate, Some(40.0));
+        assert_eq!(row.roi, Some(50.0));
+    }
+
+    #[test]
+    fn at_home_markets_only_pick_matches_they_apply_to() {
+        let filter: Filter = serde_json::from_value(serde_json::json!({
+            "id": 2,
+            "roi": 10.0,
+            "serviceName": "Favourite at home",
+            "rules": [],
+            "totalPicks": 10,
+            "successPercentage": 50.0,
+            "desiredOutcome": "Favorite wins at home",
+        }))
+        .unwrap();
+
+        let odds = |home: f32, away: f32| {
+            HashMap::from([("1".to_string(), home), ("2".to_string(), away)])
+        };
+        let matches = [
+            MatchRecord {
+                home_goals: 2,
+                away_goals: 0,
+                odds: odds(1.5, 5.0),
+                ..Default::default()
+            },
+            MatchRecord {
+                home_goals: 0,
+                away_goals: 1,
+                odds: odds(1.5, 5.0),
+                ..Default::default()
+            },
+            MatchRecord {
+                home_goals: 0,
+                away_goals: 3,
+                odds: odds(5.0, 1.5),
+                ..Default::default()
+            },
+        ];
+        let row = backtest(&filter, &matches);
+
+        assert_eq!(row.evaluated, 3);
+        assert_eq!((row.picks, row.won, row.lost, row.unpriced), (2, 1, 1, 0));
+        assert_eq!(row.roi, Some(-25.0));
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    utils::{filesystem, paths},
+};
+
+const BACKUPS_DIR: &str = "backups";
+const MANIFEST_FILE: &str = "manifest.json";
+const FILTERS_FILE: &str = "filters.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifest {
+    pub id: String,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+
+    pub command: String,
+    pub live: bool,
+    pub count: usize,
+}
+
+fn get_backups_dir() -> Result<PathBuf, CliError> {
+    let dir = paths::get_state_dir()?.join(BACKUPS_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Creates the directory of a new backup. Backups taken within the same
+/// second get a numeric suffix instead of overwriting each other.
+fn create_dir(base: &str) -> Result<(String, PathBuf), CliError> {
+    let backups = get_backups_dir()?;
+
+    for attempt in 1.. {
+        let id = match attempt {
+            1 => base.to_string(),
+            n => format!("{}-{}", base, n),
+        };
+
+        let dir = backups.join(&id);
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok((id, dir)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    unreachable!()
+}
+
+/// Archives the current `existing_*.json` snapshot before `command` changes
+/// the remote account.
+pub fn create(live: bool, command: &str) -> Result<BackupManifest, CliError> {
+    let filters: Vec<Filter> = filesystem::load_data(paths::get_existing_path(live).to_string())?;
+
+    let created_at = Utc::now();
+    let (id, dir) = create_dir(&format!(
+        "{}-{}",
+        created_at.format("%Y%m%d-%H%M%S"),
+        if live { "live" } else { "pre" }
+    ))?;
+
+    let manifest = BackupManifest {
+        id,
+        created_at,
+        command: command.to_string(),
+        live,
+        count: filters.len(),
+    };
+
+    filesystem::save_data(&dir.join(FILTERS_FILE).to_string_lossy(), &filters)?;
+    fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    log::info!("Backed up {} filters to {}", manifest.count, dir.display());
+
+    Ok(manifest)
+}
+
+pub fn list() -> Result<Vec<BackupManifest>, CliError> {
+    let mut manifests = Vec::new();
+
+    for entry in fs::read_dir(get_backups_dir()?)? {
+        let path = entry?.path().join(MANIFEST_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(content) => manifests.push(serde_json::from_str(&content)?),
+            Err(err) => log::warn!(
+                "Skipping backup without manifest {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    manifests.sort_by_key(|manifest: &BackupManifest| manifest.created_at);
+    Ok(manifests)
+}
+
+pub fn load(id: &str) -> Result<(BackupManifest, Vec<Filter>), CliError> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(CliError::ConfigError(format!("invalid backup ID '{}'", id)));
+    }
+
+    let dir = get_backups_dir()?.join(id);
+
+    if !dir.join(MANIFEST_FILE).exists() {
+        return Err(CliError::BackupNotFound(id.to_string()));
+    }
+
+    let manifest = serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST_FILE))?)?;
+    let filters = filesystem::load_data(dir.join(FILTERS_FILE).to_string_lossy().to_string())?;
+
+    Ok((manifest, filters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_rejects_ids_outside_the_backups_dir() {
+        for id in [
+            "",
+            "..",
+            "../secrets",
+            "20240101-120000-pre/..",
+            "a/b",
+            "a\\b",
+        ] {
+            assert!(
+                matches!(load(id), Err(CliError::ConfigError(_))),
+                "{:?}",
+                id
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    models::{
+        filter::Filter,
+        pick::{Pick, PickResult},
+    },
+    services::report_service::ReportRow,
+};
+
+/// How much two filters' pick histories overlap.
+///
+/// `overlap` is the share of their combined matches that both picked, and
+/// `correlation` is the Pearson correlation of their win/loss results on the
+/// shared matches that both settled. Filters that pick the same matches and
+/// win and lose together are effectively the same bet.
+#[derive(Serialize, Debug, Clone)]
+pub struct CorrelationRow {
+    pub id: i32,
+    pub name: String,
+    pub other_id: i32,
+    pub other_name: String,
+    pub shared: usize,
+    pub overlap: f64,
+    pub same_outcome: usize,
+    pub settled: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation: Option<f64>,
+}
+
+impl ReportRow for CorrelationRow {
+    fn columns(_rows: &[Self]) -> Vec<&'static str> {
+        vec![
+            "id",
+            "name",
+            "other_id",
+            "other_name",
+            "shared",
+            "overlap",
+            "same_outcome",
+            "settled",
+            "correlation",
+        ]
+    }
+
+    fn cells(&self, _columns: &[&str]) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.other_id.to_string(),
+            self.other_name.clone(),
+            self.shared.to_string(),
+            format!("{:.2}", self.overlap),
+            self.same_outcome.to_string(),
+            self.settled.to_string(),
+            self.correlation
+                .map(|correlation| format!("{:+.2}", correlation))
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+/// Pearson correlation of two equally long 0/1 series, which for binary data
+/// is the phi coefficient. Undefined when either series never varies.
+fn phi(pairs: &[(bool, bool)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let count =
+        |predicate: fn(&(bool, bool)) -> bool| pairs.iter().filter(|p| predicate(p)).count();
+    let a_wins = count(|(a, _)| *a) as f64;
+    let b_wins = count(|(_, b)| *b) as f64;
+    let both = count(|(a, b)| *a && *b) as f64;
+
+    let denominator = (a_wins * (n - a_wins) * b_wins * (n - b_wins)).sqrt();
+    if denominator <= f64::EPSILON {
+        return None;
+    }
+
+    Some((n * both - a_wins * b_wins) / denominator)
+}
+
+fn compare(a: &Filter, a_picks: &[Pick], b: &Filter, b_picks: &[Pick]) -> CorrelationRow {
+    let by_match: HashMap<String, &Pick> = b_picks.iter().map(|p| (p.match_key(), p)).collect();
+    let a_matches: HashSet<String> = a_picks.iter().map(Pick::match_key).collect();
+
+    let mut shared = 0;
+    let mut same_outcome = 0;
+    let mut results = Vec::new();
+
+    for pick in a_picks {
+        let Some(other) = by_match.get(&pick.match_key()) else {
+            continue;
+        };
+
+        shared += 1;
+
+        if pick.outcome.is_some() && pick.outcome == other.outcome {
+            same_outcome += 1;
+        }
+
+        if pick.result.is_settled() && other.result.is_settled() {
+            results.push((
+                pick.result == PickResult::Won,
+                other.result == PickResult::Won,
+            ));
+        }
+    }
+
+    let union = a_matches.len() + by_match.len() - shared;
+
+    CorrelationRow {
+        id: a.id,
+        name: a.name.clone(),
+        other_id: b.id,
+        other_name: b.name.clone(),
+        shared,
+        overlap: if union == 0 {
+            0.0
+        } else {
+            shared as f64 / union as f64
+        },
+        same_outcome,
+        settled: results.len(),
+        correlation: phi(&results),
+    }
+}
+
+/// Compares every pair of filters that have synced picks. Pairs sharing fewer
+/// than `min_shared` matches are left out, and the most overlapping pairs
+/// come first.
+pub fn correlate(
+    filters: &[Filter],
+    picks: &HashMap<i32, Vec<Pick>>,
+    min_shared: usize,
+) -> Vec<CorrelationRow> {
+    let with_picks: Vec<(&Filter, &Vec<Pick>)> = filters
+        .iter()
+        .filter_map(|filter| picks.get(&filter.id).map(|p| (filter, p)))
+        .collect();
+
+    let mut rows = Vec::new();
+
+    for (i, (a, a_picks)) in with_picks.iter().enumerate() {
+        for (b, b_picks) in &with_picks[i + 1..] {
+            let row = compare(a, a_picks, b, b_picks);
+            if row.shared > 0 && row.shared >= min_shared {
+                rows.push(row);
+            }
+        }
+    }
+
+    rows.sort_by(|a, b| {
+        b.overlap
+            .partial_cmp(&a.overlap)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.shared.cmp(&a.shared))
+    });
+
+    rows
+}
@@ -0,0 +1,750 @@
+use regex::Regex;
+use std::fmt;
+
+use crate::{
+    errors::CliError,
+    models::{filter::Filter, filter_traits::FilterScoring, scoring::ScoringProfile},
+};
+
+pub const FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "roi",
+    "roi_lower",
+    "success_rate",
+    "sr",
+    "sr_lower",
+    "picks",
+    "score",
+    "outcome",
+    "category",
+    "rules.count",
+    "rules.targets",
+    "drawdown",
+    "losing_streak",
+    "volatility",
+    "recovery",
+];
+
+/// Fields computed from synced pick history rather than the filter itself.
+pub const RISK_FIELDS: &[&str] = &["drawdown", "losing_streak", "volatility", "recovery"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Str(s) => write!(f, "'{}'", s),
+            Token::Ident(name) => f.write_str(name),
+            Token::Op(op) => f.write_str(op),
+            Token::LParen => f.write_str("("),
+            Token::RParen => f.write_str(")"),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "=~", "!~", "<", ">", "!",
+];
+
+fn parse_error(column: usize, message: impl fmt::Display) -> CliError {
+    CliError::ExpressionError(format!("column {}: {}", column + 1, message))
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CliError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+
+        if c == '(' || c == ')' {
+            tokens.push((
+                start,
+                if c == '(' {
+                    Token::LParen
+                } else {
+                    Token::RParen
+                },
+            ));
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            let mut value = String::new();
+            i += 1;
+
+            loop {
+                match chars.get(i) {
+                    None => return Err(parse_error(start, "unterminated string")),
+                    Some('\\') if chars.get(i + 1) == Some(&c) => {
+                        value.push(c);
+                        i += 2;
+                    }
+                    Some(ch) if *ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(ch) => {
+                        value.push(*ch);
+                        i += 1;
+                    }
+                }
+            }
+
+            tokens.push((start, Token::Str(value)));
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| parse_error(start, format!("invalid number '{}'", text)))?;
+            tokens.push((start, Token::Number(number)));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+
+            tokens.push((start, Token::Ident(chars[start..i].iter().collect())));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| parse_error(start, format!("unexpected character '{}'", c)))?;
+
+            tokens.push((start, Token::Op(op)));
+            i += op.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Field(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    Matches {
+        value: Box<Expr>,
+        pattern: Regex,
+        negated: bool,
+    },
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    input_len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.input_len, |(column, _)| *column)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(_, t)| t.clone());
+        self.position += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(candidate)) if *candidate == op) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, CliError> {
+        let mut expr = self.parse_and()?;
+
+        while self.eat_op("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, CliError> {
+        let mut expr = self.parse_not()?;
+
+        while self.eat_op("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, CliError> {
+        if self.eat_op("!") {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, CliError> {
+        let left = self.parse_primary()?;
+
+        let op = match self.peek() {
+            Some(Token::Op(op)) if !matches!(*op, "&&" | "||" | "!") => *op,
+            _ => return Ok(left),
+        };
+
+        let column = self.column();
+        self.position += 1;
+
+        if op == "=~" || op == "!~" {
+            let pattern_column = self.column();
+            let pattern = match self.next() {
+                Some(Token::Str(pattern)) => pattern,
+                _ => {
+                    return Err(parse_error(
+                        column,
+                        format!("'{}' expects a quoted regex", op),
+                    ))
+                }
+            };
+
+            let pattern = Regex::new(&pattern)
+                .map_err(|e| parse_error(pattern_column, format!("invalid regex: {}", e)))?;
+
+            return Ok(Expr::Matches {
+                value: Box::new(left),
+                pattern,
+                negated: op == "!~",
+            });
+        }
+
+        let comparison = match op {
+            "==" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            _ => Comparison::Ge,
+        };
+
+        let right = self.parse_primary()?;
+        Ok(Expr::Compare(comparison, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, CliError> {
+        let column = self.column();
+
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Str(s)) => Ok(Expr::Str(s)),
+            Some(Token::Ident(name)) if name == "true" => Ok(Expr::Bool(true)),
+            Some(Token::Ident(name)) if name == "false" => Ok(Expr::Bool(false)),
+            Some(Token::Ident(name)) => {
+                if !FIELDS.contains(&name.as_str()) {
+                    return Err(parse_error(
+                        column,
+                        format!(
+                            "unknown field '{}', expected one of {}",
+                            name,
+                            FIELDS.join(", ")
+                        ),
+                    ));
+                }
+                Ok(Expr::Field(name))
+            }
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err(parse_error(column, "unclosed '('")),
+                }
+            }
+            Some(token) => Err(parse_error(column, format!("unexpected '{}'", token))),
+            None => Err(parse_error(column, "unexpected end of expression")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "boolean",
+        }
+    }
+}
+
+/// A parsed criteria expression such as
+/// `roi >= 35 && picks >= 20 && outcome !~ '^C[OU]' && rules.count <= 6`.
+#[derive(Debug)]
+pub struct Criteria {
+    source: String,
+    expr: Expr,
+}
+
+impl Criteria {
+    pub fn parse(source: &str) -> Result<Self, CliError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            input_len: source.chars().count(),
+        };
+
+        let expr = parser.parse_or()?;
+
+        if let Some(token) = parser.peek() {
+            return Err(parse_error(
+                parser.column(),
+                format!("unexpected '{}'", token),
+            ));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the expression reads any of the pick history risk fields.
+    pub fn uses_risk(&self) -> bool {
+        fn visit(expr: &Expr) -> bool {
+            match expr {
+                Expr::Field(name) => RISK_FIELDS.contains(&name.as_str()),
+                Expr::Not(inner) => visit(inner),
+                Expr::And(left, right) | Expr::Or(left, right) | Expr::Compare(_, left, right) => {
+                    visit(left) || visit(right)
+                }
+                Expr::Matches { value, .. } => visit(value),
+                Expr::Number(_) | Expr::Str(_) | Expr::Bool(_) => false,
+            }
+        }
+
+        visit(&self.expr)
+    }
+
+    /// Evaluates the expression for a filter. Filters without synced pick
+    /// history never match an expression that reads risk fields, even
+    /// under `!`.
+    pub fn matches(&self, filter: &Filter, profile: &ScoringProfile) -> Result<bool, CliError> {
+        if filter.risk.is_none() && self.uses_risk() {
+            return Ok(false);
+        }
+
+        match evaluate(&self.expr, filter, profile)? {
+            Value::Bool(result) => Ok(result),
+            value => Err(CliError::ExpressionError(format!(
+                "'{}' evaluates to a {}, expected a boolean",
+                self.source,
+                value.type_name()
+            ))),
+        }
+    }
+}
+
+fn get_field(name: &str, filter: &Filter, profile: &ScoringProfile) -> Value {
+    match name {
+        "id" => Value::Number(filter.id as f64),
+        "name" => Value::Str(filter.name.clone()),
+        "roi" => Value::Number(filter.roi as f64),
+        "roi_lower" => Value::Number(filter.roi_interval(profile.z).0),
+        "success_rate" | "sr" => Value::Number(filter.success_rate as f64),
+        "sr_lower" => Value::Number(filter.success_rate_interval(profile.z).0),
+        "picks" => Value::Number(filter.total_picks as f64),
+        "score" => Value::Number(filter.score_with(profile).total()),
+        "outcome" => Value::Str(
+            filter
+                .desired_outcome
+                .as_ref()
+                .map(|market| market.to_string())
+                .unwrap_or_default(),
+        ),
+        "category" => Value::Str(
+            filter
+                .desired_outcome
+                .as_ref()
+                .map(|market| market.category().to_string())
+                .unwrap_or_default(),
+        ),
+        "rules.count" => Value::Number(filter.rules.len() as f64),
+        "rules.targets" => Value::Str(
+            filter
+                .rules
+                .iter()
+                .filter_map(|rule| rule.target.as_ref().map(|t| t.to_string()))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        // `matches` rejects filters without risk data before evaluating
+        name if RISK_FIELDS.contains(&name) => {
+            Value::Number(filter.risk.map_or(f64::NAN, |risk| match name {
+                "drawdown" => risk.max_drawdown,
+                "losing_streak" => risk.losing_streak as f64,
+                "volatility" => risk.volatility,
+                _ => risk.time_to_recover as f64,
+            }))
+        }
+        _ => unreachable!("fields are validated while parsing"),
+    }
+}
+
+fn evaluate(expr: &Expr, filter: &Filter, profile: &ScoringProfile) -> Result<Value, CliError> {
+    let as_bool = |expr: &Expr| -> Result<bool, CliError> {
+        match evaluate(expr, filter, profile)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(CliError::ExpressionError(format!(
+                "expected a boolean but found a {}",
+                value.type_name()
+            ))),
+        }
+    };
+
+    let value = match expr {
+        Expr::Number(n) => Value::Number(*n),
+        Expr::Str(s) => Value::Str(s.clone()),
+        Expr::Bool(b) => Value::Bool(*b),
+        Expr::Field(name) => get_field(name, filter, profile),
+        Expr::Not(inner) => Value::Bool(!as_bool(inner)?),
+        Expr::And(left, right) => Value::Bool(as_bool(left)? && as_bool(right)?),
+        Expr::Or(left, right) => Value::Bool(as_bool(left)? || as_bool(right)?),
+        Expr::Matches {
+            value,
+            pattern,
+            negated,
+        } => match evaluate(value, filter, profile)? {
+            Value::Str(s) => Value::Bool(pattern.is_match(&s) != *negated),
+            other => {
+                return Err(CliError::ExpressionError(format!(
+                    "regex match expects a string but found a {}",
+                    other.type_name()
+                )))
+            }
+        },
+        Expr::Compare(comparison, left, right) => {
+            let left = evaluate(left, filter, profile)?;
+            let right = evaluate(right, filter, profile)?;
+
+            let ordering = match (&left, &right) {
+                (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+                (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+                _ => {
+                    return Err(CliError::ExpressionError(format!(
+                        "cannot compare a {} with a {}",
+                        left.type_name(),
+                        right.type_name()
+                    )))
+                }
+            };
+
+            let Some(ordering) = ordering else {
+                return Ok(Value::Bool(false));
+            };
+
+            Value::Bool(match comparison {
+                Comparison::Eq => ordering.is_eq(),
+                Comparison::Ne => ordering.is_ne(),
+                Comparison::Lt => ordering.is_lt(),
+                Comparison::Le => ordering.is_le(),
+                Comparison::Gt => ordering.is_gt(),
+                Comparison::Ge => ordering.is_ge(),
+            })
+        }
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::risk::RiskMetrics, utils::settings::DEFAULT_CRITERIA_EXPRESSION};
+
+    fn filter(roi: f32, picks: u32, outcome: Option<&str>) -> Filter {
+        serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "roi": roi,
+            "serviceName": "Home goals (+2.5)",
+            "rules": [
+                {"target": "GOALS", "comparatorType": "GREATER_THAN", "valueToCompare": 1.5, "type": "HOME"},
+                {"target": "CORNERS", "comparatorType": "LESS_THAN", "valueToCompare": 9.0, "type": "TOTAL"},
+            ],
+            "totalPicks": picks,
+            "successPercentage": 60.0,
+            "desiredOutcome": outcome,
+        }))
+        .unwrap()
+    }
+
+    fn eval(expr: &str, filter: &Filter) -> bool {
+        Criteria::parse(expr)
+            .unwrap()
+            .matches(filter, &ScoringProfile::default())
+            .unwrap()
+    }
+
+    fn error(expr: &str) -> String {
+        let result = Criteria::parse(expr).and_then(|criteria| {
+            criteria.matches(&filter(50.0, 20, Some("O25")), &ScoringProfile::default())
+        });
+
+        match result {
+            Err(CliError::ExpressionError(message)) => message,
+            other => panic!(
+                "expected an expression error for '{}', got {:?}",
+                expr, other
+            ),
+        }
+    }
+
+    /// What `Filter::is_valid` accepted before criteria were configurable.
+    fn baseline_is_valid(filter: &Filter) -> bool {
+        filter.roi >= 40.0
+            && filter.total_picks >= 15
+            && filter.desired_outcome.as_ref().is_some_and(|market| {
+                let code = market.to_string();
+                !code.starts_with("CO") && !code.starts_with("CU")
+            })
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let f = filter(50.0, 20, Some("O25"));
+
+        assert!(eval("roi < 0 && picks < 0 || roi > 0", &f));
+        assert!(eval("roi > 0 || roi < 0 && picks < 0", &f));
+        assert!(!eval("(roi > 0 || roi < 0) && picks < 0", &f));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        let f = filter(50.0, 20, Some("O25"));
+
+        assert!(eval("!(roi < 0)", &f));
+        assert!(eval("!false && true", &f));
+        assert!(!eval("!(roi > 0 && picks > 0)", &f));
+        assert!(eval("!!true", &f));
+    }
+
+    #[test]
+    fn regex_operators_match_strings() {
+        let f = filter(50.0, 20, Some("O25"));
+
+        assert!(eval("outcome =~ '^O'", &f));
+        assert!(!eval("outcome !~ '^O'", &f));
+        assert!(eval("name =~ '\\(\\+2\\.5\\)$'", &f));
+        assert!(eval("rules.targets =~ 'CORNERS'", &f));
+    }
+
+    #[test]
+    fn every_field_reads_the_filter() {
+        let f = filter(50.0, 20, Some("O25"));
+        let profile = ScoringProfile::default();
+        let (roi_lower, _) = f.roi_interval(profile.z);
+        let (sr_lower, _) = f.success_rate_interval(profile.z);
+        let score = f.score_with(&profile).total();
+
+        let expressions = [
+            "id == 42".to_string(),
+            "name == 'Home goals (+2.5)'".to_string(),
+            "roi == 50".to_string(),
+            format!(
+                "roi_lower > {} && roi_lower < {}",
+                roi_lower - 0.01,
+                roi_lower + 0.01
+            ),
+            "success_rate == 60".to_string(),
+            "sr == 60".to_string(),
+            format!(
+                "sr_lower > {} && sr_lower < {}",
+                sr_lower - 0.01,
+                sr_lower + 0.01
+            ),
+            "picks == 20".to_string(),
+            format!("score > {} && score < {}", score - 0.01, score + 0.01),
+            "outcome == 'O25'".to_string(),
+            "category == 'goals'".to_string(),
+            "rules.count == 2".to_string(),
+            "rules.targets == 'GOALS,CORNERS'".to_string(),
+        ];
+
+        for expr in &expressions {
+            assert!(eval(expr, &f), "{}", expr);
+        }
+
+        let covered: Vec<&str> = expressions
+            .iter()
+            .map(|expr| expr.split_whitespace().next().unwrap())
+            .collect();
+        for field in FIELDS.iter().filter(|f| !RISK_FIELDS.contains(f)) {
+            assert!(covered.contains(field), "field '{}' is not tested", field);
+        }
+    }
+
+    #[test]
+    fn default_criteria_match_the_baseline() {
+        let filters = [
+            filter(50.0, 20, Some("O25")),
+            filter(50.0, 20, Some("1")),
+            filter(40.0, 15, Some("GG")),
+            filter(39.9, 20, Some("O25")),
+            filter(50.0, 14, Some("O25")),
+            filter(50.0, 20, None),
+            filter(50.0, 20, Some("CO95")),
+            filter(50.0, 20, Some("CU85")),
+            filter(50.0, 20, Some("CO9.5")),
+            filter(50.0, 20, Some("Over 0.5 Since Picked")),
+        ];
+
+        for f in &filters {
+            assert_eq!(
+                eval(DEFAULT_CRITERIA_EXPRESSION, f),
+                baseline_is_valid(f),
+                "roi {} picks {} outcome {:?}",
+                f.roi,
+                f.total_picks,
+                f.desired_outcome
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_fields_list_the_valid_ones() {
+        let message = error("roi >= 40 && pick >= 15");
+
+        assert!(
+            message.starts_with("column 14: unknown field 'pick'"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("expected one of id, name, roi"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn bad_tokens_report_their_column() {
+        assert_eq!(
+            error("roi >= 40 # 1"),
+            "column 11: unexpected character '#'"
+        );
+        assert_eq!(error("outcome == 'O25"), "column 12: unterminated string");
+        assert_eq!(error("(roi > 1"), "column 1: unclosed '('");
+        assert_eq!(error("roi >"), "column 6: unexpected end of expression");
+        assert_eq!(error("roi > 1 picks"), "column 9: unexpected 'picks'");
+        assert!(error("outcome =~ '['").starts_with("column 12: invalid regex"));
+        assert_eq!(
+            error("outcome =~ 1"),
+            "column 9: '=~' expects a quoted regex"
+        );
+    }
+
+    #[test]
+    fn type_mismatches_are_errors() {
+        assert_eq!(
+            error("roi == 'high'"),
+            "cannot compare a number with a string"
+        );
+        assert_eq!(
+            error("roi && true"),
+            "expected a boolean but found a number"
+        );
+        assert_eq!(error("roi + 1").split(':').next().unwrap(), "column 5");
+        assert_eq!(
+            error("roi"),
+            "'roi' evaluates to a number, expected a boolean"
+        );
+        assert_eq!(
+            error("picks =~ '1'"),
+            "regex match expects a string but found a number"
+        );
+    }
+
+    #[test]
+    fn risk_fields_read_the_synced_metrics() {
+        let mut f = filter(50.0, 20, Some("O25"));
+        f.risk = Some(RiskMetrics {
+            picks: 30,
+            max_drawdown: 6.5,
+            losing_streak: 4,
+            volatility: 0.9,
+            time_to_recover: 12,
+        });
+
+        assert!(eval("drawdown == 6.5", &f));
+        assert!(eval("losing_streak == 4", &f));
+        assert!(eval("volatility == 0.9", &f));
+        assert!(eval("recovery == 12", &f));
+        assert!(eval("!(drawdown > 10)", &f));
+    }
+
+    #[test]
+    fn filters_without_risk_data_never_match_risk_criteria() {
+        let f = filter(50.0, 20, Some("O25"));
+
+        assert!(!eval("drawdown <= 10", &f));
+        assert!(!eval("!(drawdown > 10)", &f));
+        assert!(!eval("roi > 0 || losing_streak < 5", &f));
+        assert!(eval("roi > 0", &f));
+    }
+}
@@ -0,0 +1,163 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{models::filter::Filter, services::report_service::ReportRow};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added,
+    Removed,
+    Renamed,
+    Changed,
+}
+
+impl Change {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Renamed => "renamed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+/// How one filter differs between two snapshots. Metrics are taken from the
+/// newer snapshot, except for removed filters which only exist in the older.
+#[derive(Serialize, Debug, Clone)]
+pub struct DiffRow {
+    pub change: Change,
+    pub id: i32,
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_name: Option<String>,
+
+    pub roi: f32,
+    pub success_rate: f32,
+    pub picks: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roi_delta: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_rate_delta: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picks_delta: Option<i64>,
+}
+
+impl DiffRow {
+    fn single(change: Change, filter: &Filter) -> Self {
+        Self {
+            change,
+            id: filter.id,
+            name: filter.name.clone(),
+            previous_name: None,
+            roi: filter.roi,
+            success_rate: filter.success_rate,
+            picks: filter.total_picks,
+            roi_delta: None,
+            success_rate_delta: None,
+            picks_delta: None,
+        }
+    }
+
+    fn between(before: &Filter, after: &Filter) -> Self {
+        let renamed = before.name != after.name;
+
+        Self {
+            change: if renamed {
+                Change::Renamed
+            } else {
+                Change::Changed
+            },
+            previous_name: renamed.then(|| before.name.clone()),
+            roi_delta: Some(after.roi - before.roi),
+            success_rate_delta: Some(after.success_rate - before.success_rate),
+            picks_delta: Some(after.total_picks as i64 - before.total_picks as i64),
+            ..Self::single(Change::Changed, after)
+        }
+    }
+
+    fn roi_movement(&self) -> f32 {
+        self.roi_delta.map_or(0.0, f32::abs)
+    }
+}
+
+fn format_delta<T: std::fmt::Display>(delta: Option<T>, format: impl Fn(T) -> String) -> String {
+    delta.map(format).unwrap_or_default()
+}
+
+impl ReportRow for DiffRow {
+    fn columns(_rows: &[Self]) -> Vec<&'static str> {
+        vec![
+            "change",
+            "id",
+            "name",
+            "previous_name",
+            "roi",
+            "roi_delta",
+            "success_rate",
+            "success_rate_delta",
+            "picks",
+            "picks_delta",
+        ]
+    }
+
+    fn cells(&self, _columns: &[&str]) -> Vec<String> {
+        vec![
+            self.change.as_str().to_string(),
+            self.id.to_string(),
+            self.name.clone(),
+            self.previous_name.clone().unwrap_or_default(),
+            format!("{:.2}", self.roi),
+            format_delta(self.roi_delta, |d| format!("{:+.2}", d)),
+            format!("{:.2}", self.success_rate),
+            format_delta(self.success_rate_delta, |d| format!("{:+.2}", d)),
+            self.picks.to_string(),
+            format_delta(self.picks_delta, |d| format!("{:+}", d)),
+        ]
+    }
+}
+
+/// Compares two snapshots by filter ID. Added and removed filters come first,
+/// followed by renamed or changed filters with the biggest ROI movement first.
+/// Filters that are identical in both snapshots are left out.
+pub fn diff(before: &[Filter], after: &[Filter]) -> Vec<DiffRow> {
+    let before_by_id: HashMap<i32, &Filter> = before.iter().map(|f| (f.id, f)).collect();
+    let after_by_id: HashMap<i32, &Filter> = after.iter().map(|f| (f.id, f)).collect();
+
+    let mut added: Vec<DiffRow> = after
+        .iter()
+        .filter(|f| !before_by_id.contains_key(&f.id))
+        .map(|f| DiffRow::single(Change::Added, f))
+        .collect();
+    added.sort_by_key(|row| row.id);
+
+    let mut removed: Vec<DiffRow> = before
+        .iter()
+        .filter(|f| !after_by_id.contains_key(&f.id))
+        .map(|f| DiffRow::single(Change::Removed, f))
+        .collect();
+    removed.sort_by_key(|row| row.id);
+
+    let mut changed: Vec<DiffRow> = after
+        .iter()
+        .filter_map(|f| before_by_id.get(&f.id).map(|b| DiffRow::between(b, f)))
+        .filter(|row| {
+            row.change == Change::Renamed
+                || row.roi_delta != Some(0.0)
+                || row.success_rate_delta != Some(0.0)
+                || row.picks_delta != Some(0)
+        })
+        .collect();
+    changed.sort_by(|a, b| {
+        b.roi_movement()
+            .partial_cmp(&a.roi_movement())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    added.into_iter().chain(removed).chain(changed).collect()
+}
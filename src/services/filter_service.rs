@@ -3,53 +3,223 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     errors::CliError,
-    models::filter::Filter,
-    utils::{config, filesystem},
+    models::{
+        cleanup::{CleanupThresholds, DecayThresholds, RecentWindow},
+        filter::Filter,
+        quotas::{QuotaTracker, Quotas},
+        scoring::ScoringProfile,
+    },
+    services::{
+        criteria_service::Criteria,
+        metrics_service,
+        similarity_service::{self, Neighbour},
+    },
+    utils::{config, time},
 };
 
 pub fn find_duplicates(filters: &[Filter]) -> HashMap<Filter, Vec<i32>> {
     let mut seen: HashMap<Filter, Vec<i32>> = HashMap::new();
 
     for filter in filters {
-        seen.entry(filter.clone())
-            .or_insert_with(Vec::new)
-            .push(filter.id);
+        seen.entry(filter.clone()).or_default().push(filter.id);
     }
 
     seen.into_iter().filter(|(_, ids)| ids.len() > 1).collect()
 }
 
-pub fn remove_existing_filters(
-    data: Vec<Filter>,
-    existing_path: &str,
-) -> Result<Vec<Filter>, CliError> {
-    let existing_data = filesystem::load_data(existing_path.to_string())?;
-    let existing_set: HashSet<_> = existing_data.iter().collect();
+/// Finds `(broader, narrower)` pairs where the narrower filter's picks are a
+/// subset of the broader one's. Identical filters are left to `find_duplicates`,
+/// and filters that subsume each other are reported once, lowest ID first.
+pub fn find_subsumptions(filters: &[Filter]) -> Vec<(&Filter, &Filter)> {
+    let mut pairs = Vec::new();
 
-    Ok(data
-        .into_iter()
-        .filter(|filter| !existing_set.contains(filter))
-        .collect())
+    for broader in filters {
+        for narrower in filters {
+            if broader.id == narrower.id || broader == narrower || !broader.subsumes(narrower) {
+                continue;
+            }
+
+            if narrower.subsumes(broader) && narrower.id < broader.id {
+                continue;
+            }
+
+            pairs.push((broader, narrower));
+        }
+    }
+
+    pairs
 }
 
-pub fn filter_valid_entries(filters: Vec<Filter>) -> Vec<Filter> {
-    filters
-        .into_iter()
-        .filter(|entry| entry.is_valid())
+pub fn remove_existing_filters(data: Vec<Filter>, existing: &[Filter]) -> Vec<Filter> {
+    let existing_set: HashSet<_> = existing.iter().collect();
+
+    data.into_iter()
+        .filter(|filter| !existing_set.contains(filter))
         .collect()
 }
 
-pub fn filter_low_performing(filters: Vec<Filter>) -> Vec<Filter> {
+/// Takes filters in order until `count` are selected, skipping any that would
+/// push an outcome or category held in `existing` over its quota.
+pub fn select_with_quotas(
+    sorted: Vec<Filter>,
+    existing: &[Filter],
+    quotas: &Quotas,
+    count: usize,
+) -> Vec<Filter> {
+    let mut tracker = QuotaTracker::new(quotas, existing);
+    let mut selected = Vec::new();
+    let mut skipped = 0;
+
+    for filter in sorted {
+        if selected.len() == count {
+            break;
+        }
+
+        if let Some(reason) = tracker.check(&filter) {
+            log::debug!("Skipping filter {}: {}", filter.id, reason);
+            skipped += 1;
+            continue;
+        }
+
+        tracker.add(&filter);
+        selected.push(filter);
+    }
+
+    if skipped > 0 {
+        log::info!("Skipped {} filters over their market quota", skipped);
+    }
+
+    selected
+}
+
+pub fn filter_valid_entries(
+    filters: Vec<Filter>,
+    criteria: &Criteria,
+    profile: &ScoringProfile,
+) -> Result<Vec<Filter>, CliError> {
+    let mut valid = Vec::new();
+
+    for filter in filters {
+        if criteria.matches(&filter, profile)? {
+            valid.push(filter);
+        }
+    }
+
+    Ok(valid)
+}
+
+pub fn filter_low_performing(filters: Vec<Filter>, thresholds: &CleanupThresholds) -> Vec<Filter> {
     filters
         .into_iter()
-        .filter(|filter| filter.is_low_performing())
+        .filter(|filter| filter.is_low_performing(thresholds))
         .collect()
 }
 
-pub fn sort_by_score(mut filters: Vec<Filter>) -> Vec<Filter> {
+/// Finds filters whose picks since `thresholds.since` have an ROI below
+/// `thresholds.roi_below`, using the metrics recorded on earlier fetches.
+pub fn find_decaying(
+    filters: &[Filter],
+    live: bool,
+    thresholds: &DecayThresholds,
+) -> Result<Vec<(Filter, RecentWindow)>, CliError> {
+    let since = time::parse_point_in_time(&thresholds.since)?;
+    let connection = metrics_service::open()?;
+    let mut decaying = Vec::new();
+
+    for filter in filters {
+        let Some(baseline) = metrics_service::point_at(&connection, filter.id, live, since)? else {
+            log::debug!("No metrics for filter {} before {}", filter.id, since);
+            continue;
+        };
+
+        let Some(window) = filter.recent_window(baseline.roi, baseline.picks) else {
+            continue;
+        };
+
+        if window.picks >= thresholds.min_picks && window.roi < thresholds.roi_below as f64 {
+            decaying.push((filter.clone(), window));
+        }
+    }
+
+    Ok(decaying)
+}
+
+/// Greedily picks up to `count` filters by maximal marginal relevance: each
+/// pick maximises `lambda * score - (1 - lambda) * similarity`, where scores
+/// are scaled to 0-1 across the candidates and similarity is the highest
+/// rule-set similarity to the filters already picked or held in `existing`.
+pub fn select_mmr(
+    candidates: Vec<Filter>,
+    existing: &[Filter],
+    quotas: &Quotas,
+    count: usize,
+    lambda: f64,
+    profile: &ScoringProfile,
+) -> Vec<Filter> {
+    let scores: Vec<f64> = candidates
+        .iter()
+        .map(|filter| filter.score_with(profile).total())
+        .collect();
+    let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let normalise = |score: f64| {
+        if max - min > f64::EPSILON {
+            (score - min) / (max - min)
+        } else {
+            1.0
+        }
+    };
+
+    let mut max_similarity: Vec<f64> = candidates
+        .iter()
+        .map(|candidate| {
+            similarity_service::nearest_neighbour(candidate, existing)
+                .map_or(0.0, |neighbour| neighbour.similarity)
+        })
+        .collect();
+
+    let mut tracker = QuotaTracker::new(quotas, existing);
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut picked: Vec<usize> = Vec::new();
+
+    while picked.len() < count {
+        let relevance =
+            |i: usize| lambda * normalise(scores[i]) - (1.0 - lambda) * max_similarity[i];
+
+        let best = remaining
+            .iter()
+            .copied()
+            .filter(|i| tracker.check(&candidates[*i]).is_none())
+            .max_by(|a, b| {
+                relevance(*a)
+                    .partial_cmp(&relevance(*b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+        let Some(best) = best else {
+            break;
+        };
+
+        tracker.add(&candidates[best]);
+        picked.push(best);
+        remaining.retain(|i| *i != best);
+
+        for i in &remaining {
+            let similarity =
+                similarity_service::rule_set_similarity(&candidates[*i], &candidates[best]);
+            max_similarity[*i] = max_similarity[*i].max(similarity);
+        }
+    }
+
+    let mut slots: Vec<Option<Filter>> = candidates.into_iter().map(Some).collect();
+    picked.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+pub fn sort_by_score(mut filters: Vec<Filter>, profile: &ScoringProfile) -> Vec<Filter> {
     filters.sort_by(|a, b| {
-        b.get_score()
-            .partial_cmp(&a.get_score())
+        b.score_with(profile)
+            .total()
+            .partial_cmp(&a.score_with(profile).total())
             .unwrap_or(std::cmp::Ordering::Equal)
     });
     filters
@@ -60,11 +230,14 @@ pub fn display_filters(
     open: bool,
     live: bool,
     verbose: bool,
+    profile: &ScoringProfile,
+    neighbours: &HashMap<i32, Neighbour>,
 ) -> Result<(), CliError> {
     let base_url = config::get_web_base_url(live);
 
     for (i, item) in filters.iter().enumerate() {
         let url = format!("{}/{}/history", base_url, item.id);
+        let neighbour = neighbours.get(&item.id);
 
         if open {
             if let Err(err) = open::that(&url) {
@@ -77,22 +250,100 @@ pub fn display_filters(
         }
 
         if verbose {
-            log::info!(
-                "ROI: {:.2}%\nTotal Picks: {}\nSuccess Rate: {:.2}%\nScore is {:.2}\nURL: {}",
+            let (roi_low, roi_high) = item.roi_interval(profile.z);
+            let (sr_low, sr_high) = item.success_rate_interval(profile.z);
+
+            println!(
+                "ROI: {:.2}% (CI {:.2}% to {:.2}%)\nTotal Picks: {}\nSuccess Rate: {:.2}% (CI {:.2}% to {:.2}%)\nScore is {}\nURL: {}",
                 item.roi,
+                roi_low,
+                roi_high,
                 item.total_picks,
                 item.success_rate,
-                item.get_score(),
+                sr_low,
+                sr_high,
+                item.score_with(profile),
                 url,
             );
 
+            match &item.risk {
+                Some(risk) => println!("Risk: {}", risk),
+                None => println!("Risk: no synced pick history"),
+            }
+
+            if let Some(neighbour) = neighbour {
+                println!(
+                    "Nearest neighbour: {} (similarity {:.2})",
+                    neighbour.id, neighbour.similarity
+                );
+            }
+
             if i < filters.len() - 1 {
-                log::info!("\n");
+                println!();
             }
+        } else if let Some(neighbour) = neighbour {
+            println!(
+                "{}  nearest {} ({:.2})",
+                url, neighbour.id, neighbour.similarity
+            );
         } else {
-            log::info!("{}", url);
+            println!("{}", url);
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(id: i32, rules: serde_json::Value) -> Filter {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "roi": 50.0,
+            "serviceName": format!("filter {}", id),
+            "rules": rules,
+            "totalPicks": 20,
+            "successPercentage": 60.0,
+            "desiredOutcome": "O25",
+        }))
+        .unwrap()
+    }
+
+    fn goals_above(value: f32) -> serde_json::Value {
+        serde_json::json!({
+            "target": "GOALS",
+            "comparatorType": "GREATER_THAN",
+            "valueToCompare": value,
+            "type": "HOME",
+        })
+    }
+
+    #[test]
+    fn subsumptions_point_from_broader_to_narrower() {
+        let filters = [
+            filter(1, serde_json::json!([goals_above(1.0)])),
+            filter(2, serde_json::json!([goals_above(2.0)])),
+        ];
+
+        let pairs = find_subsumptions(&filters);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].0.id, pairs[0].1.id), (1, 2));
+    }
+
+    #[test]
+    fn mutual_subsumptions_are_reported_once() {
+        // A repeated rule makes the filters differ while picking the same matches
+        let filters = [
+            filter(5, serde_json::json!([goals_above(1.0)])),
+            filter(3, serde_json::json!([goals_above(1.0), goals_above(1.0)])),
+        ];
+
+        let pairs = find_subsumptions(&filters);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].0.id, pairs[0].1.id), (3, 5));
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+};
+
+use crate::{errors::CliError, utils::paths};
+
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+#[derive(Serialize, Deserialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Import,
+    Delete,
+    Rename,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Import => f.write_str("import"),
+            Action::Delete => f.write_str("delete"),
+            Action::Rename => f.write_str("rename"),
+        }
+    }
+}
+
+/// One mutation of the remote account, appended to the journal as it happens.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+
+    /// The command line that caused the mutation
+    pub command: String,
+
+    pub action: Action,
+    pub live: bool,
+
+    /// The filter acted on. For imports this is the source filter.
+    #[serde(rename = "filterId")]
+    pub filter_id: i32,
+
+    /// The filter an import created
+    #[serde(rename = "newId", default, skip_serializing_if = "Option::is_none")]
+    pub new_id: Option<i32>,
+
+    /// The source of a deleted filter, so the delete can be undone
+    #[serde(rename = "sourceId", default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<i32>,
+
+    #[serde(
+        rename = "beforeName",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub before_name: Option<String>,
+
+    #[serde(rename = "afterName", default, skip_serializing_if = "Option::is_none")]
+    pub after_name: Option<String>,
+
+    /// `None` when the mutation succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(action: Action, live: bool, filter_id: i32) -> Self {
+        Self {
+            id: 0,
+            timestamp: Utc::now(),
+            command: std::env::args().skip(1).collect::<Vec<_>>().join(" "),
+            action,
+            live,
+            filter_id,
+            new_id: None,
+            source_id: None,
+            before_name: None,
+            after_name: None,
+            error: None,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+fn get_journal_path() -> Result<PathBuf, CliError> {
+    Ok(paths::get_state_dir()?.join(JOURNAL_FILE))
+}
+
+pub fn load() -> Result<Vec<JournalEntry>, CliError> {
+    let path = get_journal_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+
+    Ok(entries)
+}
+
+pub fn append(mut entry: JournalEntry) -> Result<JournalEntry, CliError> {
+    entry.id = load()?.last().map_or(1, |last| last.id + 1);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_journal_path()?)?;
+
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+    Ok(entry)
+}
+
+/// Appends an entry without failing the mutation it describes, which has
+/// already happened by the time it is recorded.
+pub fn record(entry: JournalEntry) {
+    if let Err(err) = append(entry) {
+        log::warn!("Failed to write journal entry: {}", err);
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+use crate::{errors::CliError, models::filter::Filter, utils::paths};
+
+const DATABASE_FILE: &str = "metrics.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS filter_metrics (
+        fetched_at   TEXT    NOT NULL,
+        live         INTEGER NOT NULL,
+        filter_id    INTEGER NOT NULL,
+        name         TEXT    NOT NULL,
+        roi          REAL    NOT NULL,
+        success_rate REAL    NOT NULL,
+        total_picks  INTEGER NOT NULL,
+        PRIMARY KEY (live, filter_id, fetched_at)
+    );
+";
+
+/// A filter's metrics as recorded by one fetch.
+#[derive(Serialize, Debug, Clone)]
+pub struct MetricPoint {
+    pub fetched_at: DateTime<Utc>,
+    pub id: i32,
+    pub name: String,
+    pub roi: f32,
+    pub success_rate: f32,
+    pub picks: u32,
+}
+
+pub fn open() -> Result<Connection, CliError> {
+    let connection = Connection::open(paths::get_state_dir()?.join(DATABASE_FILE))?;
+    connection.execute_batch(SCHEMA)?;
+    Ok(connection)
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Stores the metrics of every filter in a fetch. Fetching twice within the
+/// same second keeps the later values.
+pub fn record(filters: &[Filter], live: bool, fetched_at: DateTime<Utc>) -> Result<(), CliError> {
+    let mut connection = open()?;
+    let transaction = connection.transaction()?;
+    let fetched_at = format_timestamp(fetched_at);
+
+    {
+        let mut insert = transaction.prepare(
+            "INSERT OR REPLACE INTO filter_metrics
+                (fetched_at, live, filter_id, name, roi, success_rate, total_picks)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+
+        for filter in filters {
+            insert.execute(params![
+                fetched_at,
+                live,
+                filter.id,
+                filter.name,
+                filter.roi,
+                filter.success_rate,
+                filter.total_picks,
+            ])?;
+        }
+    }
+
+    transaction.commit()?;
+    log::debug!("Recorded metrics for {} filters", filters.len());
+
+    Ok(())
+}
+
+/// Returns the recorded metrics of a filter, oldest first.
+pub fn series(
+    filter_id: i32,
+    live: bool,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<MetricPoint>, CliError> {
+    let connection = open()?;
+    let since = since.map(format_timestamp).unwrap_or_default();
+
+    let mut query = connection.prepare(
+        "SELECT fetched_at, filter_id, name, roi, success_rate, total_picks
+         FROM filter_metrics
+         WHERE filter_id = ?1 AND live = ?2 AND fetched_at >= ?3
+         ORDER BY fetched_at",
+    )?;
+
+    let rows = query.query_map(params![filter_id, live, since], point_from_row)?;
+
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn point_from_row(row: &Row) -> rusqlite::Result<MetricPoint> {
+    let fetched_at: String = row.get(0)?;
+
+    Ok(MetricPoint {
+        fetched_at: DateTime::parse_from_rfc3339(&fetched_at)
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .unwrap_or_default(),
+        id: row.get(1)?,
+        name: row.get(2)?,
+        roi: row.get(3)?,
+        success_rate: row.get(4)?,
+        picks: row.get(5)?,
+    })
+}
+
+/// Returns the newest recorded metrics of a filter at or before `at`. Takes
+/// an open connection since it's looked up for many filters in a row.
+pub fn point_at(
+    connection: &Connection,
+    filter_id: i32,
+    live: bool,
+    at: DateTime<Utc>,
+) -> Result<Option<MetricPoint>, CliError> {
+    let point = connection
+        .query_row(
+            "SELECT fetched_at, filter_id, name, roi, success_rate, total_picks
+             FROM filter_metrics
+             WHERE filter_id = ?1 AND live = ?2 AND fetched_at <= ?3
+             ORDER BY fetched_at DESC
+             LIMIT 1",
+            params![filter_id, live, format_timestamp(at)],
+            point_from_row,
+        )
+        .optional()?;
+
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn point_at_returns_the_newest_point_not_after_the_time() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+
+        for (fetched_at, live, roi) in [
+            ("2024-01-01T00:00:00Z", false, 10.0),
+            ("2024-01-03T00:00:00Z", false, 30.0),
+            ("2024-01-02T00:00:00Z", false, 20.0),
+            ("2024-01-02T12:00:00Z", true, 99.0),
+        ] {
+            connection
+                .execute(
+                    "INSERT INTO filter_metrics VALUES (?1, ?2, 7, 'f', ?3, 50.0, 10)",
+                    params![fetched_at, live, roi],
+                )
+                .unwrap();
+        }
+
+        let roi = |timestamp| {
+            point_at(&connection, 7, false, at(timestamp))
+                .unwrap()
+                .map(|point| point.roi)
+        };
+
+        assert_eq!(roi("2023-12-31T00:00:00Z"), None);
+        assert_eq!(roi("2024-01-02T00:00:00Z"), Some(20.0));
+        assert_eq!(roi("2024-01-02T23:59:59Z"), Some(20.0));
+        assert_eq!(roi("2024-02-01T00:00:00Z"), Some(30.0));
+    }
+}
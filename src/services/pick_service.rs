@@ -0,0 +1,158 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use crate::{
+    errors::CliError,
+    models::{
+        filter::Filter,
+        market::Market,
+        pick::{Pick, PickResult},
+        risk::RiskMetrics,
+    },
+    services::metrics_service,
+    utils::time,
+};
+
+/// Picks live next to the fetched metrics, in the same database.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS picks (
+        live       INTEGER NOT NULL,
+        filter_id  INTEGER NOT NULL,
+        match_key  TEXT    NOT NULL,
+        match_id   INTEGER,
+        match_date TEXT    NOT NULL,
+        home_team  TEXT    NOT NULL,
+        away_team  TEXT    NOT NULL,
+        outcome    TEXT,
+        odds       REAL,
+        result     TEXT    NOT NULL,
+        synced_at  TEXT    NOT NULL,
+        PRIMARY KEY (live, filter_id, match_key)
+    );
+";
+
+fn open() -> Result<Connection, CliError> {
+    let connection = metrics_service::open()?;
+    connection.execute_batch(SCHEMA)?;
+    Ok(connection)
+}
+
+/// Rewrites the match date in RFC 3339 UTC, or keeps it as listed when it
+/// can't be parsed.
+fn normalize_date(pick: &Pick) -> Pick {
+    let mut pick = pick.clone();
+
+    match time::parse_timestamp(&pick.date) {
+        Some(date) => pick.date = date.to_rfc3339_opts(SecondsFormat::Secs, true),
+        None if pick.date.is_empty() => {}
+        None => log::warn!(
+            "Could not parse the date '{}' of {} - {}, picks may be out of order",
+            pick.date,
+            pick.home_team,
+            pick.away_team
+        ),
+    }
+
+    pick
+}
+
+/// Stores a filter's picks. Picks that were synced before are updated, so a
+/// pending pick picks up its result on the next sync. Match dates are stored
+/// as RFC 3339 UTC so that they sort in match order.
+pub fn record(
+    filter_id: i32,
+    live: bool,
+    picks: &[Pick],
+    synced_at: DateTime<Utc>,
+) -> Result<(), CliError> {
+    let mut connection = open()?;
+    let transaction = connection.transaction()?;
+    let synced_at = synced_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    {
+        let mut insert = transaction.prepare(
+            "INSERT OR REPLACE INTO picks
+                (live, filter_id, match_key, match_id, match_date, home_team, away_team,
+                 outcome, odds, result, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+
+        for pick in picks {
+            let pick = normalize_date(pick);
+
+            insert.execute(params![
+                live,
+                filter_id,
+                pick.match_key(),
+                pick.match_id,
+                pick.date,
+                pick.home_team,
+                pick.away_team,
+                pick.outcome.as_ref().map(|outcome| outcome.to_string()),
+                pick.odds,
+                pick.result.as_str(),
+                synced_at,
+            ])?;
+        }
+    }
+
+    transaction.commit()?;
+    log::debug!("Recorded {} picks for filter {}", picks.len(), filter_id);
+
+    Ok(())
+}
+
+/// Returns the stored picks of every filter, oldest match first.
+pub fn load_all(live: bool) -> Result<HashMap<i32, Vec<Pick>>, CliError> {
+    let connection = open()?;
+
+    let mut query = connection.prepare(
+        "SELECT filter_id, match_id, match_date, home_team, away_team, outcome, odds, result
+         FROM picks
+         WHERE live = ?1
+         ORDER BY match_date",
+    )?;
+
+    let rows = query.query_map(params![live], |row| {
+        let outcome: Option<String> = row.get(5)?;
+        let result: String = row.get(7)?;
+
+        Ok((
+            row.get::<_, i32>(0)?,
+            Pick {
+                match_id: row.get(1)?,
+                date: row.get(2)?,
+                home_team: row.get(3)?,
+                away_team: row.get(4)?,
+                outcome: outcome.map(Market::from),
+                odds: row.get(6)?,
+                result: PickResult::from(result),
+            },
+        ))
+    })?;
+
+    let mut picks: HashMap<i32, Vec<Pick>> = HashMap::new();
+    for row in rows {
+        let (filter_id, pick) = row?;
+        picks.entry(filter_id).or_default().push(pick);
+    }
+
+    Ok(picks)
+}
+
+/// Fills in the risk metrics of filters with synced picks and returns how
+/// many of them have any.
+pub fn attach_risk(filters: &mut [Filter], live: bool) -> Result<usize, CliError> {
+    let picks = load_all(live)?;
+    let mut attached = 0;
+
+    for filter in filters.iter_mut() {
+        filter.risk = picks
+            .get(&filter.id)
+            .and_then(|p| RiskMetrics::from_picks(p));
+        attached += filter.risk.is_some() as usize;
+    }
+
+    Ok(attached)
+}
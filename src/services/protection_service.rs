@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use crate::{errors::CliError, models::filter::Filter, utils::paths};
+
+const PROTECTED_FILE: &str = "protected.json";
+
+/// Filters that cleanup must never delete, either by ID or by fingerprint so
+/// that re-imported copies stay protected too.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ProtectedList {
+    #[serde(default)]
+    pub ids: BTreeSet<i32>,
+
+    #[serde(default)]
+    pub fingerprints: BTreeSet<String>,
+}
+
+impl ProtectedList {
+    pub fn is_protected(&self, filter: &Filter) -> bool {
+        self.ids.contains(&filter.id) || self.fingerprints.contains(&filter.fingerprint())
+    }
+}
+
+fn get_protected_path() -> Result<PathBuf, CliError> {
+    Ok(paths::get_state_dir()?.join(PROTECTED_FILE))
+}
+
+pub fn load() -> Result<ProtectedList, CliError> {
+    let path = get_protected_path()?;
+
+    if !path.exists() {
+        return Ok(ProtectedList::default());
+    }
+
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save(list: &ProtectedList) -> Result<(), CliError> {
+    fs::write(get_protected_path()?, serde_json::to_string_pretty(list)?)?;
+    Ok(())
+}
@@ -0,0 +1,271 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{fs, io, path::Path};
+
+use crate::{errors::CliError, models::filter::Filter, services::similarity_service::Neighbour};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned plain-text columns
+    Table,
+    /// A single JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// A GitHub-flavoured markdown table
+    Markdown,
+}
+
+/// A row of structured output. `columns` picks the table, CSV and markdown
+/// headers for a whole report, and `cells` renders one row under them.
+pub trait ReportRow: Serialize {
+    fn columns(rows: &[Self]) -> Vec<&'static str>
+    where
+        Self: Sized;
+
+    fn cells(&self, columns: &[&str]) -> Vec<String>;
+}
+
+/// One filter as it appears in structured output. `group` and `relation`
+/// are only set by reports that relate filters to each other, like dedup.
+#[derive(Serialize, Debug, Clone)]
+pub struct FilterRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+
+    pub id: i32,
+    pub name: String,
+    pub outcome: String,
+    pub roi: f32,
+    pub success_rate: f32,
+    pub picks: u32,
+    pub score: f64,
+    pub url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_id: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+impl FilterRow {
+    pub fn new(filter: &Filter, score: f64, base_url: &str) -> Self {
+        Self {
+            group: None,
+            relation: None,
+            id: filter.id,
+            name: filter.name.clone(),
+            outcome: filter
+                .desired_outcome
+                .as_ref()
+                .map(|outcome| outcome.to_string())
+                .unwrap_or_default(),
+            roi: filter.roi,
+            success_rate: filter.success_rate,
+            picks: filter.total_picks,
+            score,
+            url: format!("{}/{}/history", base_url, filter.id),
+            nearest_id: None,
+            similarity: None,
+        }
+    }
+
+    pub fn with_neighbour(mut self, neighbour: Option<&Neighbour>) -> Self {
+        self.nearest_id = neighbour.map(|n| n.id);
+        self.similarity = neighbour.map(|n| n.similarity);
+        self
+    }
+
+    pub fn in_group(mut self, group: usize, relation: &str) -> Self {
+        self.group = Some(group);
+        self.relation = Some(relation.to_string());
+        self
+    }
+}
+
+impl ReportRow for FilterRow {
+    fn columns(rows: &[Self]) -> Vec<&'static str> {
+        let mut columns = Vec::new();
+
+        if rows.iter().any(|row| row.group.is_some()) {
+            columns.extend(["group", "relation"]);
+        }
+
+        columns.extend([
+            "id",
+            "name",
+            "outcome",
+            "roi",
+            "success_rate",
+            "picks",
+            "score",
+            "url",
+        ]);
+
+        if rows.iter().any(|row| row.nearest_id.is_some()) {
+            columns.extend(["nearest_id", "similarity"]);
+        }
+
+        columns
+    }
+
+    fn cells(&self, columns: &[&str]) -> Vec<String> {
+        let mut cells = Vec::new();
+
+        if columns.contains(&"group") {
+            cells.push(self.group.map(|g| g.to_string()).unwrap_or_default());
+            cells.push(self.relation.clone().unwrap_or_default());
+        }
+
+        cells.extend([
+            self.id.to_string(),
+            self.name.clone(),
+            self.outcome.clone(),
+            format!("{:.2}", self.roi),
+            format!("{:.2}", self.success_rate),
+            self.picks.to_string(),
+            format!("{:.2}", self.score),
+            self.url.clone(),
+        ]);
+
+        if columns.contains(&"nearest_id") {
+            cells.push(self.nearest_id.map(|id| id.to_string()).unwrap_or_default());
+            cells.push(
+                self.similarity
+                    .map(|similarity| format!("{:.2}", similarity))
+                    .unwrap_or_default(),
+            );
+        }
+
+        cells
+    }
+}
+
+fn render_csv(headers: &[&str], rows: &[Vec<String>]) -> Result<String, CliError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record(headers).map_err(io::Error::from)?;
+    for row in rows {
+        writer.write_record(row).map_err(io::Error::from)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|err| err.into_error())?;
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+}
+
+fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let format_line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_line(headers.to_vec())];
+    lines.extend(
+        rows.iter()
+            .map(|row| format_line(row.iter().map(String::as_str).collect())),
+    );
+
+    lines.join("\n") + "\n"
+}
+
+pub fn render<R: ReportRow>(rows: &[R], format: OutputFormat) -> Result<String, CliError> {
+    let headers = R::columns(rows);
+    let cells: Vec<Vec<String>> = rows.iter().map(|row| row.cells(&headers)).collect();
+
+    let rendered = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(rows)? + "\n",
+        OutputFormat::Ndjson => {
+            let mut out = String::new();
+            for row in rows {
+                out.push_str(&serde_json::to_string(row)?);
+                out.push('\n');
+            }
+            out
+        }
+        OutputFormat::Csv => render_csv(&headers, &cells)?,
+        OutputFormat::Markdown => {
+            let mut out = format!("| {} |\n", headers.join(" | "));
+            out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+            for row in &cells {
+                let escaped: Vec<String> =
+                    row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                out.push_str(&format!("| {} |\n", escaped.join(" | ")));
+            }
+            out
+        }
+        OutputFormat::Table => render_table(&headers, &cells),
+    };
+
+    Ok(rendered)
+}
+
+/// Writes the rendered rows to `output`, or to stdout when no file is given.
+pub fn emit<R: ReportRow>(
+    rows: &[R],
+    format: OutputFormat,
+    output: Option<&Path>,
+) -> Result<(), CliError> {
+    let rendered = render(rows, format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered)?;
+            log::info!("Wrote {} rows to {}", rows.len(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row(&'static str);
+
+    impl ReportRow for Row {
+        fn columns(_rows: &[Self]) -> Vec<&'static str> {
+            vec!["id", "name"]
+        }
+
+        fn cells(&self, _columns: &[&str]) -> Vec<String> {
+            vec!["1".to_string(), self.0.to_string()]
+        }
+    }
+
+    #[test]
+    fn csv_quotes_cells_that_need_it() {
+        let rows = [Row("plain"), Row("Over 2.5, \"home\""), Row("two\nlines")];
+
+        assert_eq!(
+            render(&rows, OutputFormat::Csv).unwrap(),
+            "id,name\n1,plain\n1,\"Over 2.5, \"\"home\"\"\"\n1,\"two\nlines\"\n"
+        );
+    }
+}
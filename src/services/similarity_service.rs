@@ -0,0 +1,154 @@
+use crate::models::{
+    filter::{Filter, Rule},
+    rule_types::Target,
+};
+
+/// How far apart two thresholds on the same statistic may be while still
+/// describing the same rule, in the statistic's own unit.
+fn get_tolerance(target: Option<&Target>) -> f32 {
+    match target {
+        Some(Target::Goals | Target::GoalsScored | Target::GoalsConceded) => 0.2,
+        Some(Target::ExpectedGoals | Target::Points) => 0.2,
+        Some(Target::Corners | Target::Shots | Target::Position) => 1.0,
+        Some(Target::ShotsOnGoal | Target::Cards) => 0.5,
+        Some(Target::Possession | Target::Attacks | Target::DangerousAttacks) => 5.0,
+        Some(Target::Odds) => 0.1,
+        Some(Target::Unknown(_)) | None => 0.1,
+    }
+}
+
+/// Scores how alike two rules are, from 0 (unrelated) to 1 (interchangeable).
+///
+/// Thresholds within the target's tolerance count as identical, and the
+/// similarity then falls off linearly to 0 at twice the tolerance.
+pub fn rule_similarity(a: &Rule, b: &Rule) -> f64 {
+    if !a.same_condition(b) {
+        return 0.0;
+    }
+
+    match (a.value_to_compare, b.value_to_compare) {
+        (Some(x), Some(y)) => {
+            let tolerance = get_tolerance(a.target.as_ref());
+            let diff = (x - y).abs();
+
+            if diff <= tolerance {
+                1.0
+            } else {
+                (1.0 - ((diff - tolerance) / tolerance) as f64).max(0.0)
+            }
+        }
+        (None, None) => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// The filter closest to another and how similar they are.
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbour {
+    pub id: i32,
+    pub similarity: f64,
+}
+
+/// Dice coefficient over the two rule sets, where each rule is greedily paired
+/// with its most similar unused counterpart. Filters on different outcomes
+/// are never similar.
+pub fn filter_similarity(a: &Filter, b: &Filter) -> f64 {
+    if a.desired_outcome != b.desired_outcome {
+        return 0.0;
+    }
+
+    rule_set_similarity(a, b)
+}
+
+/// Like `filter_similarity`, but regardless of outcome: filters with
+/// different outcomes but the same rules still fire on the same matches.
+pub fn rule_set_similarity(a: &Filter, b: &Filter) -> f64 {
+    let total = a.rules.len() + b.rules.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let mut used = vec![false; b.rules.len()];
+    let mut matched = 0.0;
+
+    for rule in &a.rules {
+        let best = b
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !used[*i])
+            .map(|(i, other)| (i, rule_similarity(rule, other)))
+            .max_by(|x, y| x.1.partial_cmp(&y.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some((i, similarity)) = best {
+            if similarity > 0.0 {
+                used[i] = true;
+                matched += similarity;
+            }
+        }
+    }
+
+    2.0 * matched / total as f64
+}
+
+/// Finds the filter among `others` whose rules are most like `filter`'s,
+/// skipping the filter itself.
+pub fn nearest_neighbour<'a>(
+    filter: &Filter,
+    others: impl IntoIterator<Item = &'a Filter>,
+) -> Option<Neighbour> {
+    others
+        .into_iter()
+        .filter(|other| other.id != filter.id)
+        .map(|other| Neighbour {
+            id: other.id,
+            similarity: rule_set_similarity(filter, other),
+        })
+        .max_by(|a, b| {
+            a.similarity
+                .partial_cmp(&b.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Groups filters whose similarity to at least one other member reaches
+/// `threshold`. Only groups with two or more filters are returned.
+pub fn find_clusters(filters: &[Filter], threshold: f64) -> Vec<Vec<&Filter>> {
+    let mut parents: Vec<usize> = (0..filters.len()).collect();
+
+    fn find(parents: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        parents[i] = root;
+        root
+    }
+
+    for i in 0..filters.len() {
+        for j in (i + 1)..filters.len() {
+            if filter_similarity(&filters[i], &filters[j]) >= threshold {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut clusters: Vec<Vec<&Filter>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+
+    for (i, filter) in filters.iter().enumerate() {
+        let root = find(&mut parents, i);
+
+        match roots.iter().position(|r| *r == root) {
+            Some(index) => clusters[index].push(filter),
+            None => {
+                roots.push(root);
+                clusters.push(vec![filter]);
+            }
+        }
+    }
+
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{api_client::ApiClient, metrics_service},
+    utils::{filesystem, paths},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotKind {
+    /// Filters listed by the public scanner (`pre.json`/`live.json`)
+    Scanner,
+    /// Filters saved on the account (`existing_*.json`)
+    Existing,
+}
+
+impl SnapshotKind {
+    pub fn path(&self, live: bool) -> String {
+        match self {
+            SnapshotKind::Scanner => paths::get_data_path(live),
+            SnapshotKind::Existing => paths::get_existing_path(live),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotMeta {
+    #[serde(rename = "fetchedAt")]
+    pub fetched_at: DateTime<Utc>,
+
+    pub scanner: String,
+    pub count: usize,
+}
+
+/// A copy of an `existing_*.json` fetch kept under the state directory so
+/// that later fetches can be compared against it.
+#[derive(Debug, Clone)]
+pub struct ArchivedSnapshot {
+    pub id: String,
+    pub fetched_at: DateTime<Utc>,
+    pub live: bool,
+    pub path: PathBuf,
+}
+
+const ARCHIVE_DIR: &str = "snapshots";
+const ARCHIVE_ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone)]
+pub struct CachePolicy {
+    pub offline: bool,
+    pub refresh: bool,
+    pub ttl: Duration,
+}
+
+fn scanner_name(live: bool) -> &'static str {
+    if live {
+        "live"
+    } else {
+        "pre-match"
+    }
+}
+
+fn get_meta_path(data_path: &str) -> String {
+    Path::new(data_path)
+        .with_extension("meta.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Reads the snapshot metadata, falling back to the file's modification time
+/// for snapshots written before metadata was recorded.
+pub fn load_meta(data_path: &str, live: bool) -> Option<SnapshotMeta> {
+    if let Ok(content) = fs::read_to_string(get_meta_path(data_path)) {
+        if let Ok(meta) = serde_json::from_str(&content) {
+            return Some(meta);
+        }
+    }
+
+    let modified = fs::metadata(data_path).and_then(|m| m.modified()).ok()?;
+
+    Some(SnapshotMeta {
+        fetched_at: modified.into(),
+        scanner: scanner_name(live).to_string(),
+        count: 0,
+    })
+}
+
+fn age_of(meta: &SnapshotMeta) -> Duration {
+    Utc::now() - meta.fetched_at
+}
+
+fn format_age(age: Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d {}h", age.num_days(), age.num_hours() % 24)
+    } else if age.num_hours() > 0 {
+        format!("{}h {}m", age.num_hours(), age.num_minutes() % 60)
+    } else {
+        format!("{}m", age.num_minutes())
+    }
+}
+
+fn is_fresh(data_path: &str, live: bool, ttl: Duration) -> bool {
+    load_meta(data_path, live).is_some_and(|meta| age_of(&meta) <= ttl)
+}
+
+/// Fetches a snapshot's filters without storing anything locally.
+pub fn fetch_filters(client: &ApiClient, kind: SnapshotKind) -> Result<Vec<Filter>, CliError> {
+    match kind {
+        SnapshotKind::Scanner => client.fetch_scanner_filters(),
+        SnapshotKind::Existing => client.fetch_filters(),
+    }
+}
+
+pub fn fetch_snapshot(
+    client: &ApiClient,
+    kind: SnapshotKind,
+    live: bool,
+) -> Result<SnapshotMeta, CliError> {
+    let filters = fetch_filters(client, kind)?;
+    store_snapshot(&filters, kind, live)
+}
+
+/// Saves fetched filters as the cached snapshot. The account's filters are
+/// also archived and their metrics recorded.
+pub fn store_snapshot(
+    filters: &[Filter],
+    kind: SnapshotKind,
+    live: bool,
+) -> Result<SnapshotMeta, CliError> {
+    let data_path = kind.path(live);
+    filesystem::save_data(&data_path, filters)?;
+
+    let meta = SnapshotMeta {
+        fetched_at: Utc::now(),
+        scanner: scanner_name(live).to_string(),
+        count: filters.len(),
+    };
+
+    fs::write(
+        get_meta_path(&data_path),
+        serde_json::to_string_pretty(&meta)?,
+    )?;
+
+    log::info!("Fetched {} filters into {}", meta.count, data_path);
+
+    if kind == SnapshotKind::Existing {
+        // The metrics history is a by-product; losing a point beats losing the fetch
+        if let Err(err) = metrics_service::record(filters, live, meta.fetched_at) {
+            log::warn!("Could not record filter metrics: {}", err);
+        }
+
+        archive(filters, &meta, live)?;
+    }
+
+    Ok(meta)
+}
+
+fn get_archive_dir() -> Result<PathBuf, CliError> {
+    let dir = paths::get_state_dir()?.join(ARCHIVE_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn archive(filters: &[Filter], meta: &SnapshotMeta, live: bool) -> Result<(), CliError> {
+    let id = format!(
+        "{}-{}",
+        meta.fetched_at.format(ARCHIVE_ID_FORMAT),
+        if live { "live" } else { "pre" }
+    );
+    let path = get_archive_dir()?.join(format!("{}.json", id));
+
+    filesystem::save_data(&path.to_string_lossy(), filters)?;
+    log::debug!("Archived snapshot {}", id);
+
+    Ok(())
+}
+
+fn parse_archive_id(id: &str, path: PathBuf) -> Option<ArchivedSnapshot> {
+    let (timestamp, scanner) = id.rsplit_once('-')?;
+    let fetched_at = NaiveDateTime::parse_from_str(timestamp, ARCHIVE_ID_FORMAT).ok()?;
+
+    Some(ArchivedSnapshot {
+        id: id.to_string(),
+        fetched_at: fetched_at.and_utc(),
+        live: scanner == "live",
+        path,
+    })
+}
+
+/// Lists archived fetches of the account's filters, oldest first.
+pub fn list_archived(live: bool) -> Result<Vec<ArchivedSnapshot>, CliError> {
+    let mut archived = Vec::new();
+
+    for entry in fs::read_dir(get_archive_dir()?)? {
+        let path = entry?.path();
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        match parse_archive_id(id, path.clone()) {
+            Some(snapshot) if snapshot.live == live => archived.push(snapshot),
+            Some(_) => {}
+            None => log::warn!("Skipping unrecognised archive {}", path.display()),
+        }
+    }
+
+    archived.sort_by_key(|snapshot| snapshot.fetched_at);
+    Ok(archived)
+}
+
+pub fn find_archived(id: &str) -> Result<ArchivedSnapshot, CliError> {
+    let path = get_archive_dir()?.join(format!("{}.json", id));
+
+    match parse_archive_id(id, path) {
+        Some(snapshot) if snapshot.path.exists() => Ok(snapshot),
+        _ => Err(CliError::SnapshotUnavailable(format!(
+            "No archived snapshot {}",
+            id
+        ))),
+    }
+}
+
+/// Makes sure the requested snapshots exist locally, fetching them when they
+/// are missing or older than the TTL unless running offline.
+pub fn prepare(kinds: &[SnapshotKind], live: bool, policy: &CachePolicy) -> Result<(), CliError> {
+    for kind in kinds {
+        let data_path = kind.path(live);
+        let cached = Path::new(&data_path).exists();
+
+        if policy.offline {
+            if !cached {
+                return Err(CliError::SnapshotUnavailable(format!(
+                    "{} is not cached and running offline",
+                    data_path
+                )));
+            }
+        } else if policy.refresh || !is_fresh(&data_path, live, policy.ttl) {
+            if let Err(err) = fetch_snapshot(&ApiClient::new(live), *kind, live) {
+                if !cached {
+                    return Err(err);
+                }
+
+                log::warn!(
+                    "Failed to fetch {}, falling back to cached data: {}",
+                    data_path,
+                    err
+                );
+            }
+        }
+
+        warn_if_stale(&data_path, live, policy.ttl);
+    }
+
+    Ok(())
+}
+
+pub fn warn_if_stale(data_path: &str, live: bool, ttl: Duration) {
+    match load_meta(data_path, live) {
+        Some(meta) if age_of(&meta) > ttl => log::warn!(
+            "Using stale data from {}: fetched {} ago (TTL {})",
+            data_path,
+            format_age(age_of(&meta)),
+            format_age(ttl)
+        ),
+        Some(_) => {}
+        None => log::warn!("No fetch time recorded for {}", data_path),
+    }
+}
@@ -1,52 +1,63 @@
-use crate::errors::CliError;
-use std::process::Command;
-
-pub fn execute_script(script: &str, args: &[&str]) -> Result<String, CliError> {
-    let output = Command::new("bash")
-        .arg(script)
-        .args(args)
-        .output()
-        .map_err(|e| CliError::CommandFailed(format!("Failed to execute {}: {}", script, e)))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(CliError::CommandFailed(format!(
-            "Command failed: {}",
-            stderr
-        )))
+use crate::{
+    errors::CliError,
+    models::filter::Filter,
+    services::{
+        api_client::ApiClient,
+        journal_service::{self, Action, JournalEntry},
+    },
+};
+
+pub fn import_filter(client: &ApiClient, filter_id: i32, live: bool) -> Result<Filter, CliError> {
+    let result = client.copy_filter(filter_id).map_err(|e| {
+        CliError::ImportError(format!("Failed to import filter {}: {}", filter_id, e))
+    });
+
+    let mut entry = JournalEntry::new(Action::Import, live, filter_id);
+    match &result {
+        Ok(imported) => {
+            entry.new_id = Some(imported.id);
+            entry.after_name = Some(imported.name.clone());
+        }
+        Err(err) => entry.error = Some(err.to_string()),
     }
+    journal_service::record(entry);
+
+    result
 }
 
-pub fn import_filter(filter_id: i32, live: bool) -> Result<(), CliError> {
-    let base_url = crate::utils::config::get_copy_endpoint(live);
-    let import_url = format!("{}/{}", base_url, filter_id);
+pub fn delete_filter(client: &ApiClient, filter: &Filter, live: bool) -> Result<(), CliError> {
+    let result = client.delete_filter(filter.id).map_err(|e| {
+        CliError::DeletionError(format!("Failed to delete filter {}: {}", filter.id, e))
+    });
 
-    match execute_script("requests/import_filter.sh", &[&import_url]) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(CliError::ImportError(format!(
-            "Failed to import filter {}: {}",
-            filter_id, e
-        ))),
+    let mut entry = JournalEntry::new(Action::Delete, live, filter.id);
+    entry.source_id = filter.source_id;
+    entry.before_name = Some(filter.name.clone());
+    if let Err(err) = &result {
+        entry.error = Some(err.to_string());
     }
+    journal_service::record(entry);
+
+    result
 }
 
-pub fn delete_filter(filter_id: i32, live: bool) -> Result<(), CliError> {
-    let base_url = crate::utils::config::get_delete_endpoint(live);
-    let delete_url = format!("{}{}", base_url, filter_id);
+pub fn rename_filter(
+    client: &ApiClient,
+    filter: &Filter,
+    new_name: &str,
+    live: bool,
+) -> Result<Filter, CliError> {
+    let result = client.rename_filter(filter, new_name).map_err(|e| {
+        CliError::RenameError(format!("Failed to rename filter {}: {}", filter.id, e))
+    });
 
-    match execute_script("requests/delete_filter.sh", &[&delete_url]) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(CliError::DeletionError(format!(
-            "Failed to delete filter {}: {}",
-            filter_id, e
-        ))),
+    let mut entry = JournalEntry::new(Action::Rename, live, filter.id);
+    entry.before_name = Some(filter.name.clone());
+    entry.after_name = Some(new_name.to_string());
+    if let Err(err) = &result {
+        entry.error = Some(err.to_string());
     }
-}
+    journal_service::record(entry);
 
-pub fn fetch_filters() -> Result<(), CliError> {
-    execute_script("requests/fetch_filters.sh", &[])
-        .map(|_| ())
-        .map_err(|e| CliError::CommandFailed(format!("Failed to fetch filters: {}", e)))
+    result
 }
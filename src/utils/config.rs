@@ -1,37 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::settings;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct UrlConfig {
-    pub web_base_live: &'static str,
-    pub web_base_pre_match: &'static str,
-    pub api_base_live: &'static str,
-    pub api_base_pre_match: &'static str,
+    pub api_root: String,
+    pub web_base_live: String,
+    pub web_base_pre_match: String,
+    pub api_base_live: String,
+    pub api_base_pre_match: String,
 }
 
-pub const URLS: UrlConfig = UrlConfig {
-    web_base_live: "https://betmines.com/vip/live-filters",
-    web_base_pre_match: "https://betmines.com/vip/pre-match-scanner-for-football",
-    api_base_live: "https://api.betmines.com/betmines/v1/livefilters",
-    api_base_pre_match: "https://api.betmines.com/betmines/v1/preMatchfilters",
-};
+impl Default for UrlConfig {
+    fn default() -> Self {
+        Self {
+            api_root: "https://api.betmines.com/betmines/v1".into(),
+            web_base_live: "https://betmines.com/vip/live-filters".into(),
+            web_base_pre_match: "https://betmines.com/vip/pre-match-scanner-for-football".into(),
+            api_base_live: "https://api.betmines.com/betmines/v1/livefilters".into(),
+            api_base_pre_match: "https://api.betmines.com/betmines/v1/preMatchfilters".into(),
+        }
+    }
+}
 
 pub fn get_web_base_url(live: bool) -> &'static str {
+    let urls = &settings::get().urls;
+
     if live {
-        URLS.web_base_live
+        &urls.web_base_live
     } else {
-        URLS.web_base_pre_match
+        &urls.web_base_pre_match
     }
 }
 
 pub fn get_api_base_url(live: bool) -> &'static str {
+    let urls = &settings::get().urls;
+
     if live {
-        URLS.api_base_live
+        &urls.api_base_live
     } else {
-        URLS.api_base_pre_match
+        &urls.api_base_pre_match
     }
 }
 
-pub fn get_copy_endpoint(live: bool) -> String {
-    format!("{}/copyFilter", get_api_base_url(live))
+pub fn get_api_root() -> &'static str {
+    &settings::get().urls.api_root
+}
+
+pub fn login_endpoint(api_root: &str) -> String {
+    format!("{}/auth/login", api_root)
+}
+
+pub fn profile_endpoint(api_root: &str) -> String {
+    format!("{}/users/me", api_root)
+}
+
+pub fn copy_endpoint(api_base: &str) -> String {
+    format!("{}/copyFilter", api_base)
+}
+
+pub fn delete_endpoint(api_base: &str) -> String {
+    format!("{}/", api_base)
+}
+
+pub fn scanner_endpoint(api_base: &str) -> String {
+    format!("{}/public", api_base)
 }
 
-pub fn get_delete_endpoint(live: bool) -> String {
-    format!("{}/", get_api_base_url(live))
+pub fn history_endpoint(api_base: &str, filter_id: i32) -> String {
+    format!("{}/{}/history", api_base, filter_id)
 }
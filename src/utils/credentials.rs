@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{errors::CliError, utils::settings};
+
+const APP_DIR: &str = "betmines-cli";
+const CREDENTIALS_FILE: &str = "credentials.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub token: String,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+
+    #[serde(default)]
+    pub expired: bool,
+}
+
+impl Credentials {
+    pub fn new(username: String, token: String) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            username,
+            token,
+            created_at,
+            expired: false,
+        }
+    }
+}
+
+/// Builds the credentials file name of a profile. Names go into a path, so
+/// only ASCII letters, digits, `_` and `-` are allowed.
+fn credentials_file(profile: Option<&str>) -> Result<String, CliError> {
+    let Some(profile) = profile else {
+        return Ok(CREDENTIALS_FILE.to_string());
+    };
+
+    let valid = !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if !valid {
+        return Err(CliError::ConfigError(format!(
+            "Invalid profile name '{}', use only letters, digits, '_' and '-'",
+            profile
+        )));
+    }
+
+    Ok(format!("credentials.{}.json", profile))
+}
+
+/// Each profile keeps its own session so that several accounts can be used
+/// side by side.
+pub fn get_credentials_path() -> Result<PathBuf, CliError> {
+    let file = credentials_file(settings::get().profile.as_deref())?;
+
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_DIR).join(file))
+        .ok_or_else(|| CliError::AuthError("Could not determine user config directory".into()))
+}
+
+pub fn load() -> Result<Option<Credentials>, CliError> {
+    let path = get_credentials_path()?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+pub fn save(credentials: &Credentials) -> Result<(), CliError> {
+    let path = get_credentials_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&path)?;
+
+    // The mode above only applies on creation, so tighten pre-existing files too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(serde_json::to_string_pretty(credentials)?.as_bytes())?;
+
+    Ok(())
+}
+
+pub fn clear() -> Result<bool, CliError> {
+    let path = get_credentials_path()?;
+
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    fs::remove_file(path)?;
+    Ok(true)
+}
+
+/// Flags the stored session as expired after the API rejected its token.
+pub fn mark_expired() -> Result<(), CliError> {
+    if let Some(mut credentials) = load()? {
+        credentials.expired = true;
+        save(&credentials)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_map_to_their_own_file() {
+        assert_eq!(credentials_file(None).unwrap(), "credentials.json");
+        assert_eq!(
+            credentials_file(Some("work_2-b")).unwrap(),
+            "credentials.work_2-b.json"
+        );
+    }
+
+    #[test]
+    fn profile_names_cannot_leave_the_config_dir() {
+        for name in ["", "..", "../x", "a/b", "a\\b", "a.b", "prød"] {
+            assert!(
+                matches!(credentials_file(Some(name)), Err(CliError::ConfigError(_))),
+                "{:?}",
+                name
+            );
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use crate::errors;
-use std::{fs, io::BufReader, path::Path};
+use std::{
+    fs,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 pub fn load_data<T>(filename: String) -> Result<Vec<T>, errors::CliError>
 where
     T: serde::de::DeserializeOwned,
 {
     let path = Path::new(&filename);
-    let file = fs::File::open(&path).map_err(|e| errors::CliError::IoError(e))?;
+    let file = fs::File::open(path).map_err(errors::CliError::IoError)?;
 
     let reader = BufReader::new(file);
 
@@ -17,3 +21,13 @@ where
         Err(errors::CliError::UnsupportedFormat(filename))
     }
 }
+
+pub fn save_data<T>(filename: &str, data: &[T]) -> Result<(), errors::CliError>
+where
+    T: serde::Serialize,
+{
+    let file = fs::File::create(filename)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), data)?;
+
+    Ok(())
+}
@@ -1,12 +1,47 @@
+use clap::ValueEnum;
 use env_logger::{Builder, Env};
+use log::LevelFilter;
+use std::io::Write;
 
-pub fn setup_logging() {
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Diagnostics always go to stderr so that stdout only carries results.
+/// `--quiet` keeps errors only, while `-vv` and `-vvv` enable debug and trace
+/// messages; otherwise `LOG_LEVEL` applies, defaulting to info.
+pub fn setup_logging(quiet: bool, verbosity: u8, format: LogFormat) {
     let env = Env::default()
         .filter_or("LOG_LEVEL", "info")
         .write_style_or("LOG_STYLE", "auto");
 
-    Builder::from_env(env)
+    let mut builder = Builder::from_env(env);
+    builder
+        .target(env_logger::Target::Stderr)
         .format_timestamp(None)
-        .format_module_path(false)
-        .init();
+        .format_module_path(false);
+
+    if quiet {
+        builder.filter_level(LevelFilter::Error);
+    } else if verbosity >= 3 {
+        builder.filter_level(LevelFilter::Trace);
+    } else if verbosity == 2 {
+        builder.filter_level(LevelFilter::Debug);
+    }
+
+    if format == LogFormat::Json {
+        builder.format(|buf, record| {
+            let entry = serde_json::json!({
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{}", entry)
+        });
+    }
+
+    builder.init();
 }
@@ -1,29 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{errors::CliError, utils::settings};
+
+const APP_DIR: &str = "betmines-cli";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct FilePaths {
-    pub live_data: &'static str,
-    pub pre_match_data: &'static str,
-    pub existing_live: &'static str,
-    pub existing_pre_match: &'static str,
+    /// Directory the snapshot files below are relative to
+    pub data_dir: Option<String>,
+
+    /// Directory for backups, the journal and other local state
+    pub state_dir: Option<String>,
+
+    pub live_data: String,
+    pub pre_match_data: String,
+    pub existing_live: String,
+    pub existing_pre_match: String,
 }
 
-pub const PATHS: FilePaths = FilePaths {
-    live_data: "live.json",
-    pre_match_data: "pre.json",
-    existing_live: "existing_live.json",
-    existing_pre_match: "existing_pre.json",
-};
+impl Default for FilePaths {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            state_dir: None,
+            live_data: "live.json".into(),
+            pre_match_data: "pre.json".into(),
+            existing_live: "existing_live.json".into(),
+            existing_pre_match: "existing_pre.json".into(),
+        }
+    }
+}
+
+fn resolve(file: &str) -> String {
+    match &settings::get().paths.data_dir {
+        Some(dir) => Path::new(dir).join(file).to_string_lossy().into_owned(),
+        None => file.to_string(),
+    }
+}
+
+pub fn get_data_path(live: bool) -> String {
+    let paths = &settings::get().paths;
 
-pub fn get_data_path(live: bool) -> &'static str {
     if live {
-        PATHS.live_data
+        resolve(&paths.live_data)
     } else {
-        PATHS.pre_match_data
+        resolve(&paths.pre_match_data)
     }
 }
 
-pub fn get_existing_path(live: bool) -> &'static str {
+pub fn get_existing_path(live: bool) -> String {
+    let paths = &settings::get().paths;
+
     if live {
-        PATHS.existing_live
+        resolve(&paths.existing_live)
     } else {
-        PATHS.existing_pre_match
+        resolve(&paths.existing_pre_match)
     }
 }
+
+/// Directory holding local state that outlives a snapshot, such as the
+/// protected filter list.
+pub fn get_state_dir() -> Result<PathBuf, CliError> {
+    let dir = match &settings::get().paths.state_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_local_dir()
+            .map(|dir| dir.join(APP_DIR))
+            .ok_or_else(|| {
+                CliError::ConfigError("Could not determine user data directory".into())
+            })?,
+    };
+
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
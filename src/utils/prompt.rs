@@ -0,0 +1,14 @@
+use std::io::{self, BufRead, Write};
+
+use crate::errors::CliError;
+
+/// Asks a yes/no question on stderr, defaulting to no.
+pub fn confirm(question: &str) -> Result<bool, CliError> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
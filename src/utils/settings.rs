@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use toml::{Table, Value};
+
+use crate::{
+    errors::CliError,
+    models::{cleanup::CleanupThresholds, quotas::Quotas, scoring::ScoringProfile},
+    utils::{config::UrlConfig, paths::FilePaths},
+};
+
+const APP_DIR: &str = "betmines-cli";
+const USER_CONFIG_FILE: &str = "config.toml";
+const PROJECT_CONFIG_FILE: &str = "betmines.toml";
+
+/// Sections whose keys can be overridden with `BETMINES_<SECTION>_<KEY>`
+const ENV_PREFIX: &str = "BETMINES_";
+const ENV_SECTIONS: [&str; 2] = ["urls", "paths"];
+
+pub const DEFAULT_SCORER: &str = "default";
+pub const WILSON_SCORER: &str = "wilson";
+pub const DEFAULT_CRITERIA: &str = "default";
+
+/// Matches what `analyze` has always considered a candidate: a solid ROI, a
+/// minimum sample size and any market except corners.
+pub const DEFAULT_CRITERIA_EXPRESSION: &str =
+    "roi >= 40 && picks >= 15 && outcome != '' && outcome !~ '^C[OU]'";
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Settings {
+    /// Profile selected with `--profile`, `BETMINES_PROFILE` or the
+    /// top-level `profile` key
+    pub profile: Option<String>,
+
+    pub urls: UrlConfig,
+    pub paths: FilePaths,
+    pub scoring: BTreeMap<String, ScoringProfile>,
+    pub criteria: BTreeMap<String, String>,
+    pub cleanup: Option<CleanupThresholds>,
+    pub quotas: Quotas,
+
+    /// Config files that were found and applied, lowest precedence first
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
+}
+
+impl Settings {
+    /// Looks up a scoring profile by name. `default` and `wilson` are always
+    /// available and can be overridden in config.
+    pub fn get_scoring_profile(&self, name: &str) -> Result<ScoringProfile, CliError> {
+        match self.scoring.get(name) {
+            Some(profile) => Ok(profile.clone()),
+            None if name == DEFAULT_SCORER => Ok(ScoringProfile::default()),
+            None if name == WILSON_SCORER => Ok(ScoringProfile::wilson()),
+            None => Err(CliError::ConfigError(format!(
+                "Unknown scoring profile '{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Looks up a named criteria expression. `default` is always available
+    /// and can be overridden in config.
+    pub fn get_criteria(&self, name: &str) -> Result<&str, CliError> {
+        match self.criteria.get(name) {
+            Some(source) => Ok(source),
+            None if name == DEFAULT_CRITERIA => Ok(DEFAULT_CRITERIA_EXPRESSION),
+            None => Err(CliError::ConfigError(format!(
+                "Unknown criteria set '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+/// Merges `layer` into `base` key by key, descending into nested tables so
+/// that a layer only replaces the values it actually sets.
+fn merge(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(nested)) => merge(existing, nested),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn read_layer(path: &Path) -> Result<Table, CliError> {
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content)
+        .map_err(|e| CliError::ConfigError(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+fn get_config_paths(config: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(dir) = dirs::config_dir() {
+        paths.push(dir.join(APP_DIR).join(USER_CONFIG_FILE));
+    }
+
+    paths.push(config.map_or_else(|| PathBuf::from(PROJECT_CONFIG_FILE), Path::to_path_buf));
+    paths
+}
+
+fn env_layer() -> Table {
+    let mut layer = Table::new();
+
+    for (name, value) in std::env::vars() {
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let rest = rest.to_lowercase();
+
+        for section in ENV_SECTIONS {
+            if let Some(key) = rest.strip_prefix(section).and_then(|r| r.strip_prefix('_')) {
+                let mut entry = Table::new();
+                entry.insert(key.to_string(), Value::String(value.clone()));
+                merge(
+                    &mut layer,
+                    Table::from_iter([(section.into(), entry.into())]),
+                );
+            }
+        }
+    }
+
+    layer
+}
+
+/// Builds the effective settings from the built-in defaults, the user config,
+/// the project config (or `config` when given) and `BETMINES_*` environment
+/// variables, in increasing order of precedence. The selected profile's
+/// `[profiles.<name>]` table is applied on top of the merged files.
+pub fn load(config: Option<&Path>, profile: Option<&str>) -> Result<Settings, CliError> {
+    if let Some(path) = config.filter(|path| !path.exists()) {
+        return Err(CliError::ConfigError(format!(
+            "Config file {} does not exist",
+            path.display()
+        )));
+    }
+
+    let mut layers = Vec::new();
+    let mut sources = Vec::new();
+
+    for path in get_config_paths(config) {
+        if path.exists() {
+            log::debug!("Loading config from {}", path.display());
+            layers.push(read_layer(&path)?);
+            sources.push(path);
+        }
+    }
+
+    let mut profiles = Table::new();
+    let mut configured = None;
+
+    for layer in &mut layers {
+        if let Some(Value::Table(table)) = layer.remove("profiles") {
+            merge(&mut profiles, table);
+        }
+        if let Some(Value::String(name)) = layer.get("profile") {
+            configured = Some(name.clone());
+        }
+    }
+
+    let selected = profile.map(str::to_string).or(configured);
+
+    let mut effective = Table::new();
+
+    for layer in layers {
+        merge(&mut effective, layer);
+    }
+
+    if let Some(name) = &selected {
+        match profiles.remove(name) {
+            Some(Value::Table(table)) => merge(&mut effective, table),
+            _ => return Err(CliError::ConfigError(format!("Unknown profile '{}'", name))),
+        }
+        effective.insert("profile".into(), Value::String(name.clone()));
+    }
+
+    merge(&mut effective, env_layer());
+
+    let mut settings: Settings = Value::Table(effective)
+        .try_into()
+        .map_err(|e| CliError::ConfigError(e.to_string()))?;
+    settings.sources = sources;
+
+    settings.quotas.validate().map_err(CliError::ConfigError)?;
+
+    Ok(settings)
+}
+
+/// Loads the settings once for the whole run. Must be called before `get`.
+pub fn init(config: Option<&Path>, profile: Option<&str>) -> Result<&'static Settings, CliError> {
+    let settings = load(config, profile)?;
+    Ok(SETTINGS.get_or_init(|| settings))
+}
+
+pub fn get() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
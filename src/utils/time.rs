@@ -0,0 +1,136 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+
+use crate::errors::CliError;
+
+/// Parses either a relative age such as `7d`, `12h` or `30m`, meaning that
+/// long before now, or an absolute `YYYY-MM-DD` date at midnight UTC.
+pub fn parse_point_in_time(value: &str) -> Result<DateTime<Utc>, CliError> {
+    let invalid = || {
+        CliError::ConfigError(format!(
+            "Invalid time '{}', expected e.g. 7d, 12h, 30m or 2024-01-31",
+            value
+        ))
+    };
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(invalid)?;
+        return Ok(midnight.and_utc());
+    }
+
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+
+    let age = match unit {
+        'd' => Duration::try_days(amount),
+        'h' => Duration::try_hours(amount),
+        'm' => Duration::try_minutes(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    }
+    .ok_or_else(invalid)?;
+
+    Utc::now().checked_sub_signed(age).ok_or_else(invalid)
+}
+
+/// Parses a timestamp as the API lists it: RFC 3339, a date and time
+/// without an offset (taken as UTC), a bare date, or Unix milliseconds.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(timestamp.and_utc());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_relative_ages() {
+        let before = Utc::now();
+        let parsed = parse_point_in_time("7d").unwrap();
+        let after = Utc::now();
+
+        assert!(parsed >= before - Duration::days(7) && parsed <= after - Duration::days(7));
+        assert!(parse_point_in_time("12h").is_ok());
+        assert!(parse_point_in_time("30m").is_ok());
+        assert!(parse_point_in_time("2w").is_ok());
+    }
+
+    #[test]
+    fn parses_dates_at_midnight_utc() {
+        assert_eq!(
+            parse_point_in_time("2024-01-31").unwrap().to_rfc3339(),
+            "2024-01-31T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_input_without_panicking() {
+        for value in [
+            "",
+            "d",
+            "7",
+            "7y",
+            "7д",
+            "д",
+            "99999999999999d",
+            "9223372036854775807w",
+            "2024-02-30",
+        ] {
+            assert!(
+                matches!(parse_point_in_time(value), Err(CliError::ConfigError(_))),
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn parses_api_timestamps_as_utc() {
+        for value in [
+            "2024-01-31T15:00:00Z",
+            "2024-01-31T17:00:00+02:00",
+            "2024-01-31T15:00:00",
+            "2024-01-31 15:00:00",
+            "2024-01-31 15:00",
+            "1706713200000",
+        ] {
+            assert_eq!(
+                parse_timestamp(value).map(|t| t.to_rfc3339()),
+                Some("2024-01-31T15:00:00+00:00".to_string()),
+                "{}",
+                value
+            );
+        }
+
+        assert_eq!(
+            parse_timestamp("2024-01-31").map(|t| t.to_rfc3339()),
+            Some("2024-01-31T00:00:00+00:00".to_string())
+        );
+        assert_eq!(parse_timestamp("31/01/2024"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+}